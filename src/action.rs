@@ -1,6 +1,9 @@
 use serde::Deserialize;
 
+use crate::app::Mode;
+
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Action {
     Tick,
     Render,
@@ -11,6 +14,7 @@ pub enum Action {
     ClearScreen,
     Error(String),
     Help,
+    ChangeMode(Mode),
     ComponentAction(Box<dyn ComponentAction>),
 }
 
@@ -26,6 +30,7 @@ impl Clone for Action {
             Action::ClearScreen => Action::ClearScreen,
             Action::Error(msg) => Action::Error(msg.clone()),
             Action::Help => Action::Help,
+            Action::ChangeMode(mode) => Action::ChangeMode(*mode),
             Action::ComponentAction(action) => Action::ComponentAction(action.clone_box()),
         }
     }
@@ -43,6 +48,7 @@ impl PartialEq for Action {
             (Action::ClearScreen, Action::ClearScreen) => true,
             (Action::Error(msg1), Action::Error(msg2)) => msg1 == msg2,
             (Action::Help, Action::Help) => true,
+            (Action::ChangeMode(m1), Action::ChangeMode(m2)) => m1 == m2,
             (Action::ComponentAction(a1), Action::ComponentAction(a2)) => a1.name() == a2.name(),
             _ => false,
         }
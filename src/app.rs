@@ -1,5 +1,9 @@
+use std::{path::PathBuf, sync::Arc};
+
+use chrono_tz::Tz;
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
+use futures::{FutureExt, future::BoxFuture};
 use ratatui::prelude::Rect;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
@@ -7,8 +11,11 @@ use tracing::{debug, info};
 
 use crate::{
     action::Action,
+    backend::{cloudwatch::CloudWatchLogBackend, offline::OfflineLogBackend},
     components::{Component, outer_layout::OuterLayout},
     config::Config,
+    replay::{self, FileSource},
+    tail::{self, TailSource},
     tui::{Event, Tui},
 };
 
@@ -23,28 +30,75 @@ pub struct App {
     last_tick_key_events: Vec<KeyEvent>,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
+    /// Task sending the logs of a local source, started once the components are ready
+    source_task: Option<BoxFuture<'static, ()>>,
+}
+
+/// Logs read locally instead of from CloudWatch.
+pub enum LocalSource {
+    /// File given to `open`
+    File(FileSource),
+    /// Files to follow, or `-` for stdin
+    Tail(Vec<PathBuf>),
 }
 
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Mode {
     #[default]
     Home,
+    /// A text prompt has focus, so single key bindings are disabled.
+    Input,
 }
 
 impl App {
-    pub fn new(tick_rate: f64, frame_rate: f64) -> Result<Self> {
+    pub fn new(
+        tick_rate: f64,
+        frame_rate: f64,
+        region: Option<String>,
+        profile: Option<String>,
+        timezone: Option<Tz>,
+        source: Option<LocalSource>,
+    ) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
+        let mut config = Config::new()?;
+        if let Some(timezone) = timezone {
+            config.config.timezone = timezone;
+        }
+        let region = region.or_else(|| config.config.region.clone());
+        // ローカルのログを見る場合はAWSに接続しない
+        let source_task = match source {
+            Some(LocalSource::File(source)) => {
+                let messages = replay::read_logs(&source.path)?;
+                Some(replay::run(messages, source.speed, action_tx.clone()).boxed())
+            }
+            Some(LocalSource::Tail(paths)) => {
+                let sources = paths
+                    .iter()
+                    .map(|path| TailSource::open(path))
+                    .collect::<Result<Vec<_>>>()?;
+                Some(tail::run(sources, action_tx.clone()).boxed())
+            }
+            None => None,
+        };
+        let outer_layout = if source_task.is_some() {
+            let mut outer_layout = OuterLayout::new(Arc::new(OfflineLogBackend));
+            outer_layout.focus_log_stream();
+            outer_layout
+        } else {
+            OuterLayout::new(Arc::new(CloudWatchLogBackend::new(region, profile)))
+        };
         Ok(Self {
             tick_rate,
             frame_rate,
-            components: vec![Box::new(OuterLayout::new())],
+            components: vec![Box::new(outer_layout)],
             should_quit: false,
             should_suspend: false,
-            config: Config::new()?,
+            config,
             mode: Mode::Home,
             last_tick_key_events: Vec::new(),
             action_tx,
             action_rx,
+            source_task,
         })
     }
 
@@ -64,6 +118,9 @@ impl App {
         for component in self.components.iter_mut() {
             component.init(tui.size()?)?;
         }
+        if let Some(source_task) = self.source_task.take() {
+            tokio::spawn(source_task);
+        }
 
         let action_tx = self.action_tx.clone();
         loop {
@@ -143,6 +200,7 @@ impl App {
                 Action::ClearScreen => tui.terminal.clear()?,
                 Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
                 Action::Render => self.render(tui)?,
+                Action::ChangeMode(mode) => self.mode = mode,
                 _ => {}
             }
             for component in self.components.iter_mut() {
@@ -0,0 +1,250 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use color_eyre::Result;
+use futures::{future::BoxFuture, stream::BoxStream};
+use ulid::Ulid;
+
+use crate::components::{
+    log_group_list::LogGroup,
+    log_stream_list::LogStreamSummary,
+    outer_layout::{Message, log_group_name},
+};
+
+pub mod cloudwatch;
+#[cfg(test)]
+pub mod fake;
+pub mod offline;
+
+/// Stream of live tail batches. Each item is one session update from the backend.
+pub type LiveTailStream = BoxStream<'static, Result<Vec<Message>>>;
+
+/// Failure that retrying won't fix, such as expired credentials, denied access or a missing log
+/// group. Displays as the wrapped error.
+#[derive(Debug)]
+pub struct PermanentError(pub Box<dyn std::error::Error + Send + Sync>);
+
+impl std::fmt::Display for PermanentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for PermanentError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+/// Whether the error, or one of its causes, is a `PermanentError`.
+pub fn is_permanent(error: &color_eyre::Report) -> bool {
+    error.chain().any(|cause| cause.is::<PermanentError>())
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LiveTailRequest {
+    pub log_group_identifiers: Vec<String>,
+    /// CloudWatch filter pattern applied on the server side.
+    pub log_event_filter_pattern: Option<String>,
+    /// Only valid when exactly one log group is tailed.
+    pub log_stream_names: Vec<String>,
+    /// Only valid when exactly one log group is tailed. Exclusive with `log_stream_names`.
+    pub log_stream_name_prefixes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FetchEventsRequest {
+    pub log_group_identifier: String,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub filter_pattern: Option<String>,
+    pub log_stream_names: Vec<String>,
+    /// Exclusive with `log_stream_names`.
+    pub log_stream_name_prefix: Option<String>,
+    pub next_token: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventsPage {
+    pub messages: Vec<Message>,
+    pub next_token: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StartQueryRequest {
+    pub log_group_identifiers: Vec<String>,
+    pub query_string: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QueryStatus {
+    #[default]
+    Scheduled,
+    Running,
+    Complete,
+    Failed,
+    Cancelled,
+    Timeout,
+    Unknown,
+}
+
+impl QueryStatus {
+    /// Whether polling can stop.
+    pub fn is_finished(self) -> bool {
+        !matches!(self, QueryStatus::Scheduled | QueryStatus::Running)
+    }
+}
+
+impl std::fmt::Display for QueryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryResults {
+    pub status: QueryStatus,
+    pub records_matched: f64,
+    pub records_scanned: f64,
+    pub rows: Vec<QueryRow>,
+}
+
+/// One row of a Logs Insights result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueryRow {
+    /// Field name and value pairs in the order of the query, without `@ptr`.
+    pub fields: Vec<(String, String)>,
+    /// The row as a log message so that it can be shown in the detail view.
+    pub message: Message,
+}
+
+impl QueryRow {
+    /// Without a region the row has no console URL.
+    pub fn new(region: Option<&str>, fields: Vec<(String, String)>) -> Self {
+        let fields: Vec<(String, String)> = fields
+            .into_iter()
+            .filter(|(name, _)| name != "@ptr")
+            .collect();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(field_name, _)| field_name == name)
+                .map(|(_, value)| value.as_str())
+        };
+
+        // @timestampはUTCで"2025-06-07 10:00:00.000"の形式
+        let datetime = field("@timestamp")
+            .and_then(|timestamp| {
+                NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f").ok()
+            })
+            .map(|datetime| datetime.and_utc())
+            .unwrap_or_else(Utc::now);
+        let url = match (region, field("@log"), field("@logStream")) {
+            (Some(region), Some(log_group), Some(log_stream)) => {
+                Message::generate_url(region, log_group, log_stream)
+            }
+            _ => String::new(),
+        };
+        let log_group = field("@log").map_or("", log_group_name).to_string();
+        let log_stream = field("@logStream").unwrap_or_default().to_string();
+        let content = serde_json::Value::Object(
+            fields
+                .iter()
+                .map(|(name, value)| (name.clone(), serde_json::Value::String(value.clone())))
+                .collect(),
+        )
+        .to_string();
+
+        Self {
+            message: Message {
+                id: Ulid::new(),
+                content,
+                datetime,
+                log_group,
+                log_stream,
+                url,
+            },
+            fields,
+        }
+    }
+}
+
+/// `LogBackend` is the source of log groups and log events used by the components.
+///
+/// The production implementation talks to CloudWatch Logs, while tests use a scripted in-memory
+/// implementation so that the UI can be exercised without AWS.
+pub trait LogBackend: std::fmt::Debug + Send + Sync {
+    /// List all log groups visible to the current credentials.
+    fn list_log_groups(&self) -> BoxFuture<'_, Result<Vec<LogGroup>>>;
+
+    /// List the most recently written log streams of a log group, newest first.
+    fn list_log_streams(
+        &self,
+        log_group_name: String,
+    ) -> BoxFuture<'_, Result<Vec<LogStreamSummary>>>;
+
+    /// Start a live tail session and return the stream of received batches.
+    fn start_live_tail(&self, request: LiveTailRequest) -> BoxFuture<'_, Result<LiveTailStream>>;
+
+    /// Fetch a single page of past events from a log group.
+    fn fetch_events(&self, request: FetchEventsRequest) -> BoxFuture<'_, Result<EventsPage>>;
+
+    /// Start a Logs Insights query and return its id.
+    fn start_query(&self, request: StartQueryRequest) -> BoxFuture<'_, Result<String>>;
+
+    /// Poll the status and the rows found so far of a Logs Insights query.
+    fn get_query_results(&self, query_id: String) -> BoxFuture<'_, Result<QueryResults>>;
+
+    /// Cancel a running Logs Insights query.
+    fn stop_query(&self, query_id: String) -> BoxFuture<'_, Result<()>>;
+
+    /// List the profiles that can be passed to `set_profile`.
+    fn list_profiles(&self) -> Result<Vec<String>>;
+
+    /// Currently selected profile, or `None` when the default credential chain is used.
+    fn profile(&self) -> Option<String>;
+
+    /// Switch to another profile. Subsequent calls use a freshly built client.
+    fn set_profile(&self, profile: String);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_query_row_to_message() {
+        let row = QueryRow::new(
+            Some("ap-northeast-1"),
+            vec![
+                (
+                    "@timestamp".to_string(),
+                    "2025-06-07 10:00:00.123".to_string(),
+                ),
+                ("@log".to_string(), "123456789012:/ecs/cart".to_string()),
+                ("@logStream".to_string(), "task-1".to_string()),
+                ("@ptr".to_string(), "xxx".to_string()),
+            ],
+        );
+
+        assert_eq!(row.fields.len(), 3);
+        assert_eq!(
+            row.message.datetime,
+            "2025-06-07T10:00:00.123Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert!(row.message.url.contains("log-events/task-1"));
+        let content: serde_json::Value = serde_json::from_str(&row.message.content).unwrap();
+        assert_eq!(content["@logStream"], "task-1");
+        assert!(content.get("@ptr").is_none());
+
+        // リージョンが分からなければURLを作らない
+        let row = QueryRow::new(
+            None,
+            vec![
+                ("@log".to_string(), "123456789012:/ecs/cart".to_string()),
+                ("@logStream".to_string(), "task-1".to_string()),
+            ],
+        );
+        assert_eq!(row.message.url, "");
+    }
+}
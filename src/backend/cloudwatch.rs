@@ -0,0 +1,505 @@
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use aws_credential_types::provider::error::CredentialsError;
+use aws_sdk_cloudwatchlogs::{
+    Client,
+    config::Region,
+    error::{ProvideErrorMetadata, SdkError},
+    types::{LiveTailSessionLogEvent, OrderBy},
+};
+use chrono::DateTime;
+use color_eyre::Result;
+use futures::{FutureExt, StreamExt, future::BoxFuture};
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+use ulid::Ulid;
+
+use super::{
+    EventsPage, FetchEventsRequest, LiveTailRequest, LiveTailStream, LogBackend, PermanentError,
+    QueryResults, QueryRow, QueryStatus, StartQueryRequest,
+};
+use crate::components::{
+    log_group_list::LogGroup,
+    log_stream_list::LogStreamSummary,
+    outer_layout::{Message, log_group_name},
+};
+
+/// DescribeLogStreams page size. Only the most recent streams are shown.
+const LOG_STREAM_LIMIT: i32 = 50;
+
+/// Error codes for which sending the same request again can't succeed.
+const PERMANENT_ERROR_CODES: [&str; 8] = [
+    "AccessDeniedException",
+    "UnrecognizedClientException",
+    "ExpiredTokenException",
+    "InvalidSignatureException",
+    "ResourceNotFoundException",
+    "InvalidParameterException",
+    "InvalidOperationException",
+    "ValidationException",
+];
+
+/// `LogBackend` backed by the CloudWatch Logs API.
+///
+/// The SDK client is created lazily on first use, so constructing the backend never blocks.
+/// Switching profile swaps in an empty cell so that the next call builds a new client.
+#[derive(Debug, Default)]
+pub struct CloudWatchLogBackend {
+    /// Overrides the region resolved from the environment and AWS profile.
+    region: Option<String>,
+    profile: Mutex<Option<String>>,
+    client: Mutex<Arc<OnceCell<Client>>>,
+}
+
+impl CloudWatchLogBackend {
+    pub fn new(region: Option<String>, profile: Option<String>) -> Self {
+        Self {
+            region,
+            profile: Mutex::new(profile),
+            client: Mutex::default(),
+        }
+    }
+
+    async fn client(&self) -> Client {
+        let cell = self.client.lock().unwrap().clone();
+        let profile = self.profile();
+        cell.get_or_init(|| async {
+            // Initialize AWS SDK
+            debug!(
+                "Initializing AWS SDK for CloudWatch Logs (profile: {:?})",
+                profile
+            );
+            let mut loader = aws_config::from_env();
+            if let Some(profile) = profile {
+                loader = loader.profile_name(profile);
+            }
+            if let Some(region) = &self.region {
+                loader = loader.region(Region::new(region.clone()));
+            }
+            let config = loader.load().await;
+            Client::new(&config)
+        })
+        .await
+        .clone()
+    }
+
+    /// Region the client ended up with, used to build console URLs.
+    fn region(client: &Client) -> Option<String> {
+        let region = client.config().region().map(|region| region.to_string());
+        if region.is_none() {
+            warn!("Could not resolve the AWS region, logs will have no console URL");
+        }
+        region
+    }
+}
+
+impl LogBackend for CloudWatchLogBackend {
+    fn list_log_groups(&self) -> BoxFuture<'_, Result<Vec<LogGroup>>> {
+        async move {
+            let mut log_groups: Vec<LogGroup> = self
+                .client()
+                .await
+                .describe_log_groups()
+                .into_paginator()
+                .send()
+                .try_collect()
+                .await?
+                .into_iter()
+                .flat_map(|res| res.log_groups.unwrap_or_default())
+                .map(|log_group| LogGroup {
+                    creation_time: DateTime::from_timestamp_millis(
+                        log_group.creation_time.unwrap_or_default(),
+                    )
+                    .unwrap_or_default(),
+                    name: log_group.log_group_name.unwrap_or_default(),
+                    arn: log_group.log_group_arn.unwrap_or_default(),
+                })
+                .collect();
+
+            log_groups.sort_by(|a, b| {
+                b.creation_time
+                    .partial_cmp(&a.creation_time)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+            Ok(log_groups)
+        }
+        .boxed()
+    }
+
+    fn list_log_streams(
+        &self,
+        log_group_name: String,
+    ) -> BoxFuture<'_, Result<Vec<LogStreamSummary>>> {
+        async move {
+            let output = self
+                .client()
+                .await
+                .describe_log_streams()
+                .log_group_name(log_group_name)
+                .order_by(OrderBy::LastEventTime)
+                .descending(true)
+                .limit(LOG_STREAM_LIMIT)
+                .send()
+                .await?;
+
+            Ok(output
+                .log_streams()
+                .iter()
+                .map(|log_stream| LogStreamSummary {
+                    name: log_stream.log_stream_name().unwrap_or_default().to_string(),
+                    last_event_time: log_stream
+                        .last_event_timestamp()
+                        .and_then(DateTime::from_timestamp_millis),
+                })
+                .collect())
+        }
+        .boxed()
+    }
+
+    fn start_live_tail(&self, request: LiveTailRequest) -> BoxFuture<'_, Result<LiveTailStream>> {
+        async move {
+            let client = self.client().await;
+            let region = Self::region(&client);
+            let receiver = client
+                .start_live_tail()
+                .set_log_group_identifiers(Some(request.log_group_identifiers))
+                .set_log_event_filter_pattern(request.log_event_filter_pattern)
+                .set_log_stream_names(
+                    (!request.log_stream_names.is_empty()).then_some(request.log_stream_names),
+                )
+                .set_log_stream_name_prefixes(
+                    (!request.log_stream_name_prefixes.is_empty())
+                        .then_some(request.log_stream_name_prefixes),
+                )
+                .send()
+                .await
+                .map_err(classify)?
+                .response_stream;
+
+            let stream = futures::stream::unfold(receiver, move |mut receiver| {
+                let region = region.clone();
+                async move {
+                    loop {
+                        match receiver.recv().await {
+                            Ok(Some(log_event)) => {
+                                // セッション開始イベントはスキップ
+                                let Ok(update) = log_event.as_session_update() else {
+                                    continue;
+                                };
+                                let new_messages = update
+                                    .session_results()
+                                    .iter()
+                                    .filter_map(|session_result| {
+                                        to_message(region.as_deref(), session_result)
+                                    })
+                                    .collect::<Vec<_>>();
+                                return Some((Ok(new_messages), receiver));
+                            }
+                            Ok(None) => {
+                                debug!("No more log events to process.");
+                                return None;
+                            }
+                            Err(e) => return Some((Err(classify(e)), receiver)),
+                        }
+                    }
+                }
+            });
+            Ok(stream.boxed())
+        }
+        .boxed()
+    }
+
+    fn fetch_events(&self, request: FetchEventsRequest) -> BoxFuture<'_, Result<EventsPage>> {
+        async move {
+            let client = self.client().await;
+            let region = Self::region(&client);
+            let output = client
+                .filter_log_events()
+                .log_group_identifier(&request.log_group_identifier)
+                .set_start_time(request.start_time.map(|t| t.timestamp_millis()))
+                .set_end_time(request.end_time.map(|t| t.timestamp_millis()))
+                .set_filter_pattern(request.filter_pattern)
+                .set_log_stream_names(
+                    (!request.log_stream_names.is_empty()).then_some(request.log_stream_names),
+                )
+                .set_log_stream_name_prefix(request.log_stream_name_prefix)
+                .set_next_token(request.next_token)
+                .send()
+                .await?;
+
+            let messages = output
+                .events()
+                .iter()
+                .filter_map(|event| {
+                    let log_stream = event.log_stream_name()?;
+                    Some(Message {
+                        id: Ulid::new(),
+                        content: event.message()?.to_string(),
+                        datetime: DateTime::from_timestamp_millis(event.timestamp()?)?,
+                        log_group: log_group_name(&request.log_group_identifier).to_string(),
+                        log_stream: log_stream.to_string(),
+                        url: region
+                            .as_deref()
+                            .map(|region| {
+                                Message::generate_url(
+                                    region,
+                                    &request.log_group_identifier,
+                                    log_stream,
+                                )
+                            })
+                            .unwrap_or_default(),
+                    })
+                })
+                .collect();
+
+            Ok(EventsPage {
+                messages,
+                next_token: output.next_token().map(str::to_string),
+            })
+        }
+        .boxed()
+    }
+
+    fn start_query(&self, request: StartQueryRequest) -> BoxFuture<'_, Result<String>> {
+        async move {
+            let output = self
+                .client()
+                .await
+                .start_query()
+                .set_log_group_identifiers(Some(request.log_group_identifiers))
+                .query_string(request.query_string)
+                .start_time(request.start_time.timestamp())
+                .end_time(request.end_time.timestamp())
+                .send()
+                .await?;
+            output
+                .query_id()
+                .map(str::to_string)
+                .ok_or_else(|| color_eyre::eyre::eyre!("StartQuery returned no query id"))
+        }
+        .boxed()
+    }
+
+    fn get_query_results(&self, query_id: String) -> BoxFuture<'_, Result<QueryResults>> {
+        async move {
+            let client = self.client().await;
+            let region = Self::region(&client);
+            let output = client.get_query_results().query_id(query_id).send().await?;
+
+            let status = match output.status().map(|status| status.as_str()) {
+                Some("Scheduled") => QueryStatus::Scheduled,
+                Some("Running") => QueryStatus::Running,
+                Some("Complete") => QueryStatus::Complete,
+                Some("Failed") => QueryStatus::Failed,
+                Some("Cancelled") => QueryStatus::Cancelled,
+                Some("Timeout") => QueryStatus::Timeout,
+                _ => QueryStatus::Unknown,
+            };
+            let rows = output
+                .results()
+                .iter()
+                .map(|fields| {
+                    let fields = fields
+                        .iter()
+                        .filter_map(|field| {
+                            Some((field.field()?.to_string(), field.value()?.to_string()))
+                        })
+                        .collect();
+                    QueryRow::new(region.as_deref(), fields)
+                })
+                .collect();
+
+            Ok(QueryResults {
+                status,
+                records_matched: output.statistics().map_or(0.0, |s| s.records_matched()),
+                records_scanned: output.statistics().map_or(0.0, |s| s.records_scanned()),
+                rows,
+            })
+        }
+        .boxed()
+    }
+
+    fn stop_query(&self, query_id: String) -> BoxFuture<'_, Result<()>> {
+        async move {
+            self.client()
+                .await
+                .stop_query()
+                .query_id(query_id)
+                .send()
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn list_profiles(&self) -> Result<Vec<String>> {
+        let mut profiles = BTreeSet::new();
+        if let Some(path) = shared_file_path("AWS_CONFIG_FILE", "config") {
+            profiles.extend(parse_profile_names(&read_or_empty(path)?, true));
+        }
+        if let Some(path) = shared_file_path("AWS_SHARED_CREDENTIALS_FILE", "credentials") {
+            profiles.extend(parse_profile_names(&read_or_empty(path)?, false));
+        }
+        Ok(profiles.into_iter().collect())
+    }
+
+    fn profile(&self) -> Option<String> {
+        self.profile.lock().unwrap().clone()
+    }
+
+    fn set_profile(&self, profile: String) {
+        *self.profile.lock().unwrap() = Some(profile);
+        *self.client.lock().unwrap() = Arc::default();
+    }
+}
+
+fn shared_file_path(env_var: &str, file_name: &str) -> Option<PathBuf> {
+    env::var_os(env_var).map(PathBuf::from).or_else(|| {
+        directories::BaseDirs::new().map(|dirs| dirs.home_dir().join(".aws").join(file_name))
+    })
+}
+
+fn read_or_empty(path: PathBuf) -> Result<String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Extract profile names from a shared config or credentials file.
+///
+/// The config file names profiles `[profile foo]` (except `[default]`), while the credentials
+/// file uses bare `[foo]` sections. Other config sections such as `[sso-session foo]` are skipped.
+fn parse_profile_names(content: &str, is_config_file: bool) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let section = line.trim().strip_prefix('[')?.strip_suffix(']')?.trim();
+            if !is_config_file || section == "default" {
+                return Some(section.to_string());
+            }
+            section
+                .strip_prefix("profile")
+                .filter(|name| name.starts_with(char::is_whitespace))
+                .map(|name| name.trim().to_string())
+        })
+        .collect()
+}
+
+/// Mark the errors that retrying won't fix, so that the live tail gives up at once.
+fn classify<E, R>(error: SdkError<E, R>) -> color_eyre::Report
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+    R: std::fmt::Debug + Send + Sync + 'static,
+{
+    let permanent = error
+        .code()
+        .is_some_and(|code| PERMANENT_ERROR_CODES.contains(&code))
+        || is_credentials_error(&error);
+    if permanent {
+        PermanentError(Box::new(error)).into()
+    } else {
+        error.into()
+    }
+}
+
+/// Credentials that are missing or can't be loaded surface as a dispatch failure.
+fn is_credentials_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    std::iter::successors(Some(error), |error| error.source())
+        .any(|cause| cause.is::<CredentialsError>())
+}
+
+fn to_message(region: Option<&str>, session_result: &LiveTailSessionLogEvent) -> Option<Message> {
+    let log_group_identifier = session_result.log_group_identifier()?;
+    let log_stream = session_result.log_stream_name()?;
+    Some(Message {
+        id: Ulid::new(),
+        content: session_result.message()?.to_string(),
+        datetime: DateTime::from_timestamp_millis(session_result.timestamp()?)?,
+        log_group: log_group_name(log_group_identifier).to_string(),
+        log_stream: log_stream.to_string(),
+        url: region
+            .map(|region| Message::generate_url(region, log_group_identifier, log_stream))
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::is_permanent;
+
+    #[test]
+    fn test_parse_profile_names() {
+        let config = r#"
+[default]
+region = ap-northeast-1
+
+[profile dev]
+region = us-east-1
+
+[sso-session corp]
+sso_start_url = https://example.awsapps.com/start
+
+[profile  prod ]
+sso_session = corp
+"#;
+        assert_eq!(
+            parse_profile_names(config, true),
+            vec!["default", "dev", "prod"]
+        );
+
+        let credentials = r#"
+[default]
+aws_access_key_id = AKIA
+
+[legacy]
+aws_access_key_id = AKIA
+"#;
+        assert_eq!(
+            parse_profile_names(credentials, false),
+            vec!["default", "legacy"]
+        );
+    }
+
+    #[test]
+    fn test_classify() {
+        use aws_sdk_cloudwatchlogs::{
+            error::{ConnectorError, ErrorMetadata},
+            operation::start_live_tail::StartLiveTailError,
+        };
+
+        let service_error = |code: &str| {
+            SdkError::<_, ()>::service_error(
+                StartLiveTailError::generic(ErrorMetadata::builder().code(code).build()),
+                (),
+            )
+        };
+        assert!(is_permanent(&classify(service_error(
+            "AccessDeniedException"
+        ))));
+        assert!(is_permanent(&classify(service_error(
+            "ResourceNotFoundException"
+        ))));
+        assert!(!is_permanent(&classify(service_error(
+            "ThrottlingException"
+        ))));
+
+        let no_credentials =
+            SdkError::<StartLiveTailError, ()>::dispatch_failure(ConnectorError::other(
+                Box::new(CredentialsError::not_loaded(
+                    "no credentials in the profile",
+                )),
+                None,
+            ));
+        assert!(is_permanent(&classify(no_credentials)));
+        let timeout = SdkError::<StartLiveTailError, ()>::timeout_error("timed out");
+        assert!(!is_permanent(&classify(timeout)));
+    }
+}
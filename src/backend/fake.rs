@@ -0,0 +1,253 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use color_eyre::{Report, Result, eyre::eyre};
+use futures::{FutureExt, StreamExt, future::BoxFuture};
+
+use super::{
+    EventsPage, FetchEventsRequest, LiveTailRequest, LiveTailStream, LogBackend, PermanentError,
+    QueryResults, StartQueryRequest,
+};
+use crate::components::{
+    log_group_list::LogGroup, log_stream_list::LogStreamSummary, outer_layout::Message,
+};
+
+/// Scripted in-memory `LogBackend` for tests.
+///
+/// Each live tail session replays the next scripted session and then ends; sessions beyond the
+/// script stay open without events. Requests are recorded so that tests can assert on what the
+/// components asked for.
+#[derive(Debug, Default)]
+pub struct FakeLogBackend {
+    log_groups: Vec<LogGroup>,
+    log_groups_error: Option<String>,
+    log_streams: Vec<LogStreamSummary>,
+    live_tail_sessions: Vec<Vec<Vec<Message>>>,
+    /// Every live tail fails to start with this message, as a `PermanentError` if the flag is set.
+    live_tail_error: Option<(String, bool)>,
+    /// Time every live tail takes to connect
+    live_tail_delay: std::time::Duration,
+    events_pages: Vec<EventsPage>,
+    /// Searches of these log groups fail with the message, as a `PermanentError` if the flag is
+    /// set.
+    events_errors: HashMap<String, (String, bool)>,
+    /// Returned by successive polls of every query. The last one is repeated.
+    query_results: Vec<QueryResults>,
+    profiles: Vec<String>,
+    profile: Mutex<Option<String>>,
+    live_tail_requests: Mutex<Vec<LiveTailRequest>>,
+    fetch_events_requests: Mutex<Vec<FetchEventsRequest>>,
+    start_query_requests: Mutex<Vec<StartQueryRequest>>,
+    query_polls: Mutex<usize>,
+    stopped_queries: Mutex<Vec<String>>,
+}
+
+impl FakeLogBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn log_groups(mut self, log_groups: Vec<LogGroup>) -> Self {
+        self.log_groups = log_groups;
+        self
+    }
+
+    pub fn log_groups_error(mut self, message: &str) -> Self {
+        self.log_groups_error = Some(message.to_string());
+        self
+    }
+
+    pub fn log_streams(mut self, log_streams: Vec<LogStreamSummary>) -> Self {
+        self.log_streams = log_streams;
+        self
+    }
+
+    pub fn live_tail_batches(self, batches: Vec<Vec<Message>>) -> Self {
+        self.live_tail_sessions(vec![batches])
+    }
+
+    pub fn live_tail_sessions(mut self, sessions: Vec<Vec<Vec<Message>>>) -> Self {
+        self.live_tail_sessions = sessions;
+        self
+    }
+
+    pub fn live_tail_error(mut self, message: &str, permanent: bool) -> Self {
+        self.live_tail_error = Some((message.to_string(), permanent));
+        self
+    }
+
+    pub fn live_tail_delay(mut self, delay: std::time::Duration) -> Self {
+        self.live_tail_delay = delay;
+        self
+    }
+
+    pub fn events_pages(mut self, pages: Vec<EventsPage>) -> Self {
+        self.events_pages = pages;
+        self
+    }
+
+    pub fn events_error(mut self, log_group: &str, message: &str, permanent: bool) -> Self {
+        self.events_errors
+            .insert(log_group.to_string(), (message.to_string(), permanent));
+        self
+    }
+
+    pub fn query_results(mut self, results: Vec<QueryResults>) -> Self {
+        self.query_results = results;
+        self
+    }
+
+    pub fn profiles(mut self, profiles: Vec<String>) -> Self {
+        self.profiles = profiles;
+        self
+    }
+
+    pub fn live_tail_requests(&self) -> Vec<LiveTailRequest> {
+        self.live_tail_requests.lock().unwrap().clone()
+    }
+
+    pub fn fetch_events_requests(&self) -> Vec<FetchEventsRequest> {
+        self.fetch_events_requests.lock().unwrap().clone()
+    }
+
+    pub fn start_query_requests(&self) -> Vec<StartQueryRequest> {
+        self.start_query_requests.lock().unwrap().clone()
+    }
+
+    pub fn stopped_queries(&self) -> Vec<String> {
+        self.stopped_queries.lock().unwrap().clone()
+    }
+}
+
+impl LogBackend for FakeLogBackend {
+    fn list_log_groups(&self) -> BoxFuture<'_, Result<Vec<LogGroup>>> {
+        let result = match &self.log_groups_error {
+            Some(message) => Err(eyre!(message.clone())),
+            None => Ok(self.log_groups.clone()),
+        };
+        async move { result }.boxed()
+    }
+
+    fn list_log_streams(
+        &self,
+        _log_group_name: String,
+    ) -> BoxFuture<'_, Result<Vec<LogStreamSummary>>> {
+        let log_streams = self.log_streams.clone();
+        async move { Ok(log_streams) }.boxed()
+    }
+
+    fn start_live_tail(&self, request: LiveTailRequest) -> BoxFuture<'_, Result<LiveTailStream>> {
+        let mut requests = self.live_tail_requests.lock().unwrap();
+        let session = self.live_tail_sessions.get(requests.len()).cloned();
+        requests.push(request);
+        let delay = self.live_tail_delay;
+        if let Some((message, permanent)) = &self.live_tail_error {
+            let error = error(message, *permanent);
+            return async move {
+                tokio::time::sleep(delay).await;
+                Err(error)
+            }
+            .boxed();
+        }
+        let stream = match session {
+            Some(batches) => futures::stream::iter(batches.into_iter().map(Ok)).boxed(),
+            None => futures::stream::pending().boxed(),
+        };
+        async move {
+            tokio::time::sleep(delay).await;
+            Ok(stream)
+        }
+        .boxed()
+    }
+
+    fn fetch_events(&self, request: FetchEventsRequest) -> BoxFuture<'_, Result<EventsPage>> {
+        // ページ番号は既に受け付けたリクエスト数から決める
+        let mut requests = self.fetch_events_requests.lock().unwrap();
+        let page = self
+            .events_pages
+            .get(requests.len())
+            .cloned()
+            .unwrap_or_default();
+        let result = match self.events_errors.get(&request.log_group_identifier) {
+            Some((message, permanent)) => Err(error(message, *permanent)),
+            None => Ok(page),
+        };
+        requests.push(request);
+        async move { result }.boxed()
+    }
+
+    fn start_query(&self, request: StartQueryRequest) -> BoxFuture<'_, Result<String>> {
+        let mut requests = self.start_query_requests.lock().unwrap();
+        requests.push(request);
+        let query_id = format!("query-{}", requests.len());
+        async move { Ok(query_id) }.boxed()
+    }
+
+    fn get_query_results(&self, _query_id: String) -> BoxFuture<'_, Result<QueryResults>> {
+        let mut polls = self.query_polls.lock().unwrap();
+        let results = self
+            .query_results
+            .get(*polls)
+            .or(self.query_results.last())
+            .cloned()
+            .unwrap_or_default();
+        *polls += 1;
+        async move { Ok(results) }.boxed()
+    }
+
+    fn stop_query(&self, query_id: String) -> BoxFuture<'_, Result<()>> {
+        self.stopped_queries.lock().unwrap().push(query_id);
+        async move { Ok(()) }.boxed()
+    }
+
+    fn list_profiles(&self) -> Result<Vec<String>> {
+        Ok(self.profiles.clone())
+    }
+
+    fn profile(&self) -> Option<String> {
+        self.profile.lock().unwrap().clone()
+    }
+
+    fn set_profile(&self, profile: String) {
+        *self.profile.lock().unwrap() = Some(profile);
+    }
+}
+
+fn error(message: &str, permanent: bool) -> Report {
+    if permanent {
+        PermanentError(message.to_string().into()).into()
+    } else {
+        eyre!(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch_events_pages() {
+        let backend = FakeLogBackend::new().events_pages(vec![
+            EventsPage {
+                messages: Vec::new(),
+                next_token: Some("token".to_string()),
+            },
+            EventsPage::default(),
+        ]);
+
+        let first = backend
+            .fetch_events(FetchEventsRequest::default())
+            .await
+            .unwrap();
+        assert_eq!(first.next_token, Some("token".to_string()));
+
+        let second = backend
+            .fetch_events(FetchEventsRequest {
+                next_token: first.next_token,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(second.next_token, None);
+        assert_eq!(backend.fetch_events_requests().len(), 2);
+    }
+}
@@ -0,0 +1,57 @@
+use color_eyre::{Result, eyre::eyre};
+use futures::{FutureExt, future::BoxFuture};
+
+use super::{
+    EventsPage, FetchEventsRequest, LiveTailRequest, LiveTailStream, LogBackend, QueryResults,
+    StartQueryRequest,
+};
+use crate::components::{log_group_list::LogGroup, log_stream_list::LogStreamSummary};
+
+const UNAVAILABLE: &str = "Not available while viewing local logs";
+
+/// `LogBackend` used while viewing a file or stdin. It has no log groups and never connects to AWS.
+#[derive(Debug, Default)]
+pub struct OfflineLogBackend;
+
+impl LogBackend for OfflineLogBackend {
+    fn list_log_groups(&self) -> BoxFuture<'_, Result<Vec<LogGroup>>> {
+        async { Ok(Vec::new()) }.boxed()
+    }
+
+    fn list_log_streams(
+        &self,
+        _log_group_name: String,
+    ) -> BoxFuture<'_, Result<Vec<LogStreamSummary>>> {
+        async { Ok(Vec::new()) }.boxed()
+    }
+
+    fn start_live_tail(&self, _request: LiveTailRequest) -> BoxFuture<'_, Result<LiveTailStream>> {
+        async { Err(eyre!(UNAVAILABLE)) }.boxed()
+    }
+
+    fn fetch_events(&self, _request: FetchEventsRequest) -> BoxFuture<'_, Result<EventsPage>> {
+        async { Err(eyre!(UNAVAILABLE)) }.boxed()
+    }
+
+    fn start_query(&self, _request: StartQueryRequest) -> BoxFuture<'_, Result<String>> {
+        async { Err(eyre!(UNAVAILABLE)) }.boxed()
+    }
+
+    fn get_query_results(&self, _query_id: String) -> BoxFuture<'_, Result<QueryResults>> {
+        async { Err(eyre!(UNAVAILABLE)) }.boxed()
+    }
+
+    fn stop_query(&self, _query_id: String) -> BoxFuture<'_, Result<()>> {
+        async { Ok(()) }.boxed()
+    }
+
+    fn list_profiles(&self) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    fn profile(&self) -> Option<String> {
+        None
+    }
+
+    fn set_profile(&self, _profile: String) {}
+}
@@ -1,6 +1,12 @@
-use clap::Parser;
+use std::path::PathBuf;
 
-use crate::config::{get_config_dir, get_data_dir};
+use chrono_tz::Tz;
+use clap::{Parser, Subcommand};
+
+use crate::{
+    config::{get_config_dir, get_data_dir},
+    date::parse_timezone,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
@@ -12,6 +18,49 @@ pub struct Cli {
     /// Frame rate, i.e. number of frames per second
     #[arg(short, long, value_name = "FLOAT", default_value_t = 60.0)]
     pub frame_rate: f64,
+
+    /// AWS region, overrides the config file and the region resolved from the environment
+    #[arg(short, long, value_name = "REGION")]
+    pub region: Option<String>,
+
+    /// AWS profile to use instead of the default credential chain
+    #[arg(short, long, value_name = "PROFILE")]
+    pub profile: Option<String>,
+
+    /// Display timezone: an IANA name such as `Asia/Tokyo`, `local` or `utc`
+    #[arg(long, value_name = "TIMEZONE", value_parser = parse_timezone)]
+    pub tz: Option<Tz>,
+
+    /// Local files to follow like `tail -F` instead of CloudWatch, or `-` to read stdin
+    #[arg(value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// View an exported NDJSON / JSON file or plain text logs without connecting to AWS
+    Open {
+        /// File to open
+        file: PathBuf,
+
+        /// Replay the logs at the pace of their timestamps instead of loading them at once
+        #[arg(long)]
+        replay: bool,
+
+        /// Replay speed, e.g. `10` replays 10 times faster than the original
+        #[arg(long, value_name = "FLOAT", default_value_t = 1.0, value_parser = parse_speed, requires = "replay")]
+        speed: f64,
+    },
+}
+
+fn parse_speed(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!("invalid speed: {s} (expected a positive number)")),
+    }
 }
 
 const VERSION_MESSAGE: &str = concat!(
@@ -0,0 +1,110 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use arboard::Clipboard;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use color_eyre::{Result, eyre::eyre};
+
+/// Copy `text` to the clipboard.
+///
+/// Tries the configured command (e.g. `pbcopy`, `wl-copy`), then the system clipboard, then
+/// OSC 52 so that copying also works over SSH and in containers without a display server.
+pub fn copy_to_clipboard(text: &str, command: Option<&[String]>) -> Result<()> {
+    let mut errors = Vec::new();
+    if let Some(command) = command {
+        match copy_with_command(text, command) {
+            Ok(()) => return Ok(()),
+            Err(e) => errors.push(format!("{e:#}")),
+        }
+    }
+    match Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text)) {
+        Ok(()) => return Ok(()),
+        Err(e) => errors.push(format!("System clipboard: {e}")),
+    }
+    match copy_with_osc52(text) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            errors.push(format!("OSC 52: {e}"));
+            Err(eyre!(errors.join(", ")))
+        }
+    }
+}
+
+/// Write the text to the stdin of the command.
+fn copy_with_command(text: &str, command: &[String]) -> Result<()> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(eyre!("Clipboard command is empty"));
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| eyre!("Failed to run {program}: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Ask the terminal to set the clipboard. Terminals without OSC 52 ignore it silently.
+fn copy_with_osc52(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text, std::env::var_os("TMUX").is_some()).as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn osc52_sequence(text: &str, tmux: bool) -> String {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    if tmux {
+        // tmuxの中では外側の端末に届くようにパススルーで包む
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hello", false), "\x1b]52;c;aGVsbG8=\x07");
+        assert_eq!(
+            osc52_sequence("hello", true),
+            "\x1bPtmux;\x1b\x1b]52;c;aGVsbG8=\x07\x1b\\"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_with_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clipboard.txt");
+        // パスはクォートせずに済むようにシェルの引数で渡す
+        let command = [
+            "sh".to_string(),
+            "-c".to_string(),
+            r#"cat > "$1""#.to_string(),
+            "sh".to_string(),
+            path.display().to_string(),
+        ];
+        copy_to_clipboard("copied text", Some(&command)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "copied text");
+
+        assert!(copy_with_command("", &["false".to_string()]).is_err());
+        assert!(copy_with_command("", &[]).is_err());
+    }
+}
@@ -9,10 +9,21 @@ use tokio::sync::mpsc::UnboundedSender;
 use crate::{action::Action, config::Config, tui::Event};
 
 // pub mod fps;
+pub mod display_filter;
+pub mod error_panel;
+pub mod insights;
+pub mod json_tree;
 pub mod log_detail;
 pub mod log_group_list;
+pub mod log_level;
+pub mod log_search;
 pub mod log_stream;
+pub mod log_stream_list;
 pub mod outer_layout;
+pub mod profile_picker;
+pub mod text_area;
+pub mod text_input;
+pub mod toast;
 // pub mod home;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
@@ -114,6 +125,7 @@ pub trait Component {
     /// * `Result<Option<Action>>` - An action to be processed or none.
     fn update(&mut self, action: Action, tx: UnboundedSender<Action>) -> Result<()> {
         let _ = action; // to appease clippy
+        let _ = tx;
         Ok(())
     }
     /// Render the component on the screen. (REQUIRED)
@@ -0,0 +1,249 @@
+use serde_json::Value;
+
+use super::outer_layout::Message;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Operator {
+    /// `field=value`, ignoring case
+    Equal,
+    /// `field!=value`
+    NotEqual,
+    /// `field~value`, the field contains the value ignoring case
+    Contains,
+    /// `field!~value`
+    NotContains,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Condition {
+    /// A bare word matches anywhere in the message text.
+    Text(String),
+    Field {
+        path: Vec<String>,
+        operator: Operator,
+        value: String,
+    },
+}
+
+/// Client-side filter of the rows shown in the Log Stream, e.g. `level=ERROR and service~cart`.
+///
+/// Conditions are combined with `and` / `or` (`and` binds tighter) and negated with `not`.
+/// Field names are dotted paths into the JSON message; `message` alone is the whole text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisplayFilter {
+    pub expression: String,
+    /// Disjunction of conjunctions of (negated, condition)
+    clauses: Vec<Vec<(bool, Condition)>>,
+}
+
+impl DisplayFilter {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let tokens = tokenize(expression)?;
+        if tokens.is_empty() {
+            return Err("Empty display filter".to_string());
+        }
+
+        let mut clauses = vec![Vec::new()];
+        let mut negated = false;
+        let mut expect_condition = true;
+        for (token, quoted) in tokens {
+            let keyword = (!quoted).then(|| token.to_lowercase());
+            match keyword.as_deref() {
+                Some("and") | Some("or") if expect_condition => {
+                    return Err(format!("Unexpected '{token}' in display filter"));
+                }
+                Some("and") => expect_condition = true,
+                Some("or") => {
+                    clauses.push(Vec::new());
+                    expect_condition = true;
+                }
+                Some("not") if expect_condition => negated = !negated,
+                _ if !expect_condition => {
+                    return Err(format!("Expected 'and' or 'or' before '{token}'"));
+                }
+                _ => {
+                    let condition = if quoted {
+                        Condition::Text(token.to_lowercase())
+                    } else {
+                        parse_condition(&token)?
+                    };
+                    clauses.last_mut().unwrap().push((negated, condition));
+                    negated = false;
+                    expect_condition = false;
+                }
+            }
+        }
+        if expect_condition {
+            return Err("Display filter ends without a condition".to_string());
+        }
+
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            clauses,
+        })
+    }
+
+    /// `json` is the content already parsed as JSON, if it is.
+    pub fn matches(&self, message: &Message, json: Option<&Value>) -> bool {
+        let text = message.content.to_lowercase();
+        self.clauses.iter().any(|clause| {
+            clause.iter().all(|(negated, condition)| {
+                condition_matches(condition, &text, &message.content, json) != *negated
+            })
+        })
+    }
+}
+
+/// Split on whitespace, keeping double quoted parts together. The flag tells a quoted bare word.
+fn tokenize(expression: &str) -> Result<Vec<(String, bool)>, String> {
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    for c in expression.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                // `"foo bar"` のように先頭から引用されている場合は本文の検索として扱う
+                if in_quotes && token.is_empty() {
+                    quoted = true;
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !token.is_empty() {
+                    tokens.push((std::mem::take(&mut token), quoted));
+                }
+                quoted = false;
+            }
+            c => token.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("Unterminated quote in display filter".to_string());
+    }
+    if !token.is_empty() {
+        tokens.push((token, quoted));
+    }
+    Ok(tokens)
+}
+
+fn parse_condition(token: &str) -> Result<Condition, String> {
+    let Some(index) = token.find(['=', '~']) else {
+        return Ok(Condition::Text(token.to_lowercase()));
+    };
+
+    let is_not = token[..index].ends_with('!');
+    let field = if is_not {
+        &token[..index - 1]
+    } else {
+        &token[..index]
+    };
+    let operator = match (&token[index..index + 1], is_not) {
+        ("=", false) => Operator::Equal,
+        ("=", true) => Operator::NotEqual,
+        (_, false) => Operator::Contains,
+        (_, true) => Operator::NotContains,
+    };
+    let field = field.trim_start_matches("$.");
+    if field.is_empty() {
+        return Err(format!("Missing field name in '{token}'"));
+    }
+
+    Ok(Condition::Field {
+        path: field.split('.').map(str::to_string).collect(),
+        operator,
+        value: token[index + 1..].to_lowercase(),
+    })
+}
+
+fn condition_matches(condition: &Condition, text: &str, raw: &str, json: Option<&Value>) -> bool {
+    let (path, operator, expected) = match condition {
+        Condition::Text(word) => return text.contains(word.as_str()),
+        Condition::Field {
+            path,
+            operator,
+            value,
+        } => (path, operator, value),
+    };
+
+    let field = match json {
+        Some(json) => path
+            .iter()
+            .try_fold(json, |value, key| value.get(key))
+            .map(|value| match value {
+                Value::String(s) => s.to_lowercase(),
+                value => value.to_string().to_lowercase(),
+            }),
+        None => None,
+    };
+    // JSONでないログや`message`フィールドが無いログでは本文全体を`message`として扱う
+    let field = match field {
+        Some(field) => Some(field),
+        None if path.len() == 1 && path[0] == "message" => Some(raw.to_lowercase()),
+        None => None,
+    };
+
+    match (operator, field) {
+        (Operator::Equal, Some(field)) => field == *expected,
+        (Operator::Contains, Some(field)) => field.contains(expected.as_str()),
+        (Operator::NotEqual, Some(field)) => field != *expected,
+        (Operator::NotContains, Some(field)) => !field.contains(expected.as_str()),
+        // フィールドが無いログは肯定の条件にはマッチせず、否定の条件にはマッチする
+        (Operator::Equal | Operator::Contains, None) => false,
+        (Operator::NotEqual | Operator::NotContains, None) => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::outer_layout::message;
+
+    fn matches(filter: &DisplayFilter, message: &Message) -> bool {
+        filter.matches(
+            message,
+            serde_json::from_str(&message.content).ok().as_ref(),
+        )
+    }
+
+    #[test]
+    fn test_fields_and_operators() {
+        let error = message(r#"{"level":"error","service":"cart-api","http":{"status":500}}"#);
+        let info = message(r#"{"level":"INFO","service":"checkout"}"#);
+        let plain = message("START RequestId: abc");
+
+        let filter = DisplayFilter::parse("level=ERROR and service~cart").unwrap();
+        assert!(matches(&filter, &error));
+        assert!(!matches(&filter, &info));
+        assert!(!matches(&filter, &plain));
+
+        let filter = DisplayFilter::parse("$.http.status=500 or level!=info").unwrap();
+        assert!(matches(&filter, &error));
+        assert!(!matches(&filter, &info));
+        assert!(matches(&filter, &plain));
+
+        let filter = DisplayFilter::parse("not requestid or \"RequestId: xyz\"").unwrap();
+        assert!(matches(&filter, &error));
+        assert!(matches(&filter, &info));
+        assert!(!matches(&filter, &plain));
+
+        assert!(matches(
+            &DisplayFilter::parse("message~requestid").unwrap(),
+            &plain
+        ));
+        assert!(!matches(
+            &DisplayFilter::parse("service!~cart").unwrap(),
+            &error
+        ));
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(DisplayFilter::parse("").is_err());
+        assert!(DisplayFilter::parse("level=ERROR and").is_err());
+        assert!(DisplayFilter::parse("or level=ERROR").is_err());
+        assert!(DisplayFilter::parse("level=ERROR service~cart").is_err());
+        assert!(DisplayFilter::parse("=ERROR").is_err());
+        assert!(DisplayFilter::parse("\"ERROR").is_err());
+    }
+}
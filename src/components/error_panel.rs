@@ -0,0 +1,258 @@
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{
+    Component,
+    toast::{Corner, ToastStack},
+};
+use crate::{action::Action, app::Mode, config::Config};
+
+/// How long a new error stays visible as a toast.
+const TOAST_DURATION_SECS: i64 = 8;
+/// Older errors are dropped from the history.
+const MAX_HISTORY: usize = 200;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ErrorEntry {
+    pub datetime: DateTime<Utc>,
+    pub message: String,
+}
+
+/// Collects `Action::Error`s, shows the latest ones as toasts and keeps the full history.
+#[derive(Clone, Debug)]
+pub struct ErrorPanel {
+    config: Config,
+    errors: Vec<ErrorEntry>,
+    toasts: ToastStack,
+    is_open: bool,
+    list_state: ListState,
+}
+
+impl ErrorPanel {
+    pub fn new() -> Self {
+        Self {
+            config: Config::default(),
+            errors: Vec::new(),
+            toasts: ToastStack::new(
+                Duration::seconds(TOAST_DURATION_SECS),
+                Style::new().fg(Color::Red),
+                Corner::BottomRight,
+            ),
+            is_open: false,
+            list_state: ListState::default(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Single key bindings such as `q` are disabled while open.
+    pub fn open(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        // 最新のエラーにカーソルを合わせる
+        self.list_state.select(self.errors.len().checked_sub(1));
+        self.is_open = true;
+        tx.send(Action::ChangeMode(Mode::Input))?;
+        Ok(())
+    }
+
+    pub fn close(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        self.is_open = false;
+        tx.send(Action::ChangeMode(Mode::Home))?;
+        Ok(())
+    }
+
+    fn push(&mut self, message: String) {
+        let datetime = Utc::now();
+        self.toasts.push(
+            "Error (! for history)".to_string(),
+            format!("{} {}", self.format_time(datetime), message),
+        );
+        self.errors.push(ErrorEntry { datetime, message });
+        if self.errors.len() > MAX_HISTORY {
+            self.errors.remove(0);
+        }
+    }
+
+    fn format_time(&self, datetime: DateTime<Utc>) -> String {
+        datetime
+            .with_timezone(&self.config.config.timezone)
+            .format("%H:%M:%S")
+            .to_string()
+    }
+
+    fn draw_history(&mut self, frame: &mut Frame, area: Rect) {
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Percentage(10),
+            Constraint::Percentage(80),
+            Constraint::Percentage(10),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::vertical([
+            Constraint::Percentage(15),
+            Constraint::Percentage(70),
+            Constraint::Percentage(15),
+        ])
+        .areas(area);
+
+        let items: Vec<ListItem> = self
+            .errors
+            .iter()
+            .map(|error| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{} ", self.format_time(error.datetime)),
+                        Style::new().fg(Color::Red),
+                    ),
+                    Span::raw(error.message.clone()),
+                ]))
+            })
+            .collect();
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::new().reversed())
+                .block(Block::bordered().title(format!(
+                    "Errors ({}) - Esc: close, x: clear",
+                    self.errors.len()
+                ))),
+            area,
+            &mut self.list_state,
+        );
+
+        // 長いメッセージは選択中のものだけ折り返して全文を表示する
+        if let Some(error) = self
+            .list_state
+            .selected()
+            .and_then(|index| self.errors.get(index))
+        {
+            let detail_height = (area.height / 3).max(3);
+            let detail_area = Rect::new(
+                area.x,
+                area.bottom().saturating_sub(detail_height),
+                area.width,
+                detail_height,
+            );
+            frame.render_widget(Clear, detail_area);
+            frame.render_widget(
+                Paragraph::new(error.message.as_str())
+                    .wrap(Wrap { trim: false })
+                    .block(Block::bordered().title("Detail")),
+                detail_area,
+            );
+        }
+    }
+}
+
+impl Component for ErrorPanel {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_key_event(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        match key.code {
+            crossterm::event::KeyCode::Up => {
+                self.list_state.select_previous();
+            }
+            crossterm::event::KeyCode::Down => {
+                self.list_state.select_next();
+            }
+            crossterm::event::KeyCode::Char('x') => {
+                self.errors.clear();
+                self.toasts.clear();
+                self.list_state.select(None);
+            }
+            crossterm::event::KeyCode::Esc | crossterm::event::KeyCode::Char('!') => {
+                self.close(&tx)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action, _tx: UnboundedSender<Action>) -> Result<()> {
+        if let Action::Error(message) = action {
+            self.push(message);
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if self.is_open {
+            self.draw_history(frame, area);
+        } else {
+            self.toasts.draw(frame, area);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn test_collect_errors() {
+        let mut error_panel = ErrorPanel::new();
+        let (tx, _rx) = mpsc::unbounded_channel();
+
+        error_panel
+            .update(Action::Error("AccessDenied".to_string()), tx.clone())
+            .unwrap();
+        error_panel.update(Action::Tick, tx.clone()).unwrap();
+        error_panel
+            .update(Action::Error("ExpiredToken".to_string()), tx)
+            .unwrap();
+
+        assert_eq!(error_panel.errors.len(), 2);
+        let now = Utc::now();
+        let recent: Vec<_> = error_panel
+            .toasts
+            .recent(now)
+            .iter()
+            .map(|toast| toast.body.split_once(' ').unwrap().1)
+            .collect();
+        assert_eq!(recent, vec!["ExpiredToken", "AccessDenied"]);
+        assert!(
+            error_panel
+                .toasts
+                .recent(now + Duration::seconds(TOAST_DURATION_SECS))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_open_and_clear_history() {
+        let mut error_panel = ErrorPanel::new();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        for message in ["a", "b"] {
+            error_panel
+                .update(Action::Error(message.to_string()), tx.clone())
+                .unwrap();
+        }
+
+        // 開いている間は`q`などのキーバインドを無効にする
+        error_panel.open(&tx).unwrap();
+        assert_eq!(rx.try_recv().unwrap(), Action::ChangeMode(Mode::Input));
+        assert_eq!(error_panel.list_state.selected(), Some(1));
+        error_panel
+            .handle_key_event(crossterm::event::KeyCode::Char('x').into(), tx.clone())
+            .unwrap();
+        assert!(error_panel.errors.is_empty());
+        error_panel
+            .handle_key_event(crossterm::event::KeyCode::Esc.into(), tx)
+            .unwrap();
+        assert!(!error_panel.is_open());
+        assert_eq!(rx.try_recv().unwrap(), Action::ChangeMode(Mode::Home));
+    }
+}
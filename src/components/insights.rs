@@ -0,0 +1,627 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+use ulid::Ulid;
+
+use super::{
+    Component,
+    log_group_list::SelectLogGroup,
+    log_stream::SelectLog,
+    profile_picker::SelectProfile,
+    text_area::TextArea,
+    text_input::{InputOutcome, TextInput},
+};
+use crate::{
+    action::{Action, ComponentAction},
+    app::Mode,
+    backend::{LogBackend, QueryResults, QueryStatus, StartQueryRequest},
+    config::Config,
+    date::TimeRange,
+};
+
+const DEFAULT_QUERY: &str =
+    "fields @timestamp, @message, @logStream, @log\n| sort @timestamp desc\n| limit 100";
+const DEFAULT_TIME_RANGE: &str = "-1h";
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_COLUMN_WIDTH: usize = 40;
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+
+#[derive(Clone, Debug, PartialEq)]
+enum QueryUpdate {
+    Results(QueryResults),
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct UpdateQuery {
+    /// Updates of a query started before the latest run are ignored.
+    run_id: Ulid,
+    update: QueryUpdate,
+}
+impl ComponentAction for UpdateQuery {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "UpdateQuery"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Focus {
+    #[default]
+    Query,
+    TimeRange,
+    Results,
+}
+
+#[derive(Clone, Debug)]
+struct QueryRun {
+    id: Ulid,
+    started_at: DateTime<Utc>,
+    finished_at: Option<DateTime<Utc>>,
+    cancel_token: CancellationToken,
+}
+
+/// Logs Insights query editor and its results over the selected log groups.
+#[derive(Clone, Debug)]
+pub struct Insights {
+    backend: Arc<dyn LogBackend>,
+    config: Config,
+    is_open: bool,
+    focus: Focus,
+    log_group_identifiers: Vec<String>,
+    query: TextArea,
+    time_range: TextInput,
+    run: Option<QueryRun>,
+    results: QueryResults,
+    columns: Vec<String>,
+    table_state: TableState,
+}
+
+impl Insights {
+    pub fn new(backend: Arc<dyn LogBackend>) -> Self {
+        Self {
+            backend,
+            config: Config::default(),
+            is_open: false,
+            focus: Focus::default(),
+            log_group_identifiers: Vec::new(),
+            query: TextArea::new(DEFAULT_QUERY),
+            time_range: TextInput::new(DEFAULT_TIME_RANGE),
+            run: None,
+            results: QueryResults::default(),
+            columns: Vec::new(),
+            table_state: TableState::default(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// The editor takes every key, so single key bindings are disabled while open.
+    pub fn open(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        self.is_open = true;
+        tx.send(Action::ChangeMode(Mode::Input))?;
+        Ok(())
+    }
+
+    pub fn close(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        self.is_open = false;
+        tx.send(Action::ChangeMode(Mode::Home))?;
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.run
+            .as_ref()
+            .is_some_and(|run| run.finished_at.is_none())
+    }
+
+    fn run_query(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        if self.log_group_identifiers.is_empty() {
+            tx.send(Action::Error(
+                "Select log groups to run a Logs Insights query".to_string(),
+            ))?;
+            return Ok(());
+        }
+        let time_range =
+            match TimeRange::parse(self.time_range.value(), self.config.config.timezone) {
+                Ok(time_range) => time_range,
+                Err(e) => {
+                    tx.send(Action::Error(e))?;
+                    return Ok(());
+                }
+            };
+        self.cancel_query();
+
+        let now = Utc::now();
+        let (start_time, end_time) = time_range.resolve(now);
+        let request = StartQueryRequest {
+            log_group_identifiers: self.log_group_identifiers.clone(),
+            query_string: self.query.value(),
+            start_time,
+            end_time: end_time.unwrap_or(now),
+        };
+        let run = QueryRun {
+            id: Ulid::new(),
+            started_at: now,
+            finished_at: None,
+            cancel_token: CancellationToken::new(),
+        };
+        tokio::spawn(poll_query(
+            self.backend.clone(),
+            request,
+            run.id,
+            tx.clone(),
+            run.cancel_token.clone(),
+        ));
+
+        self.run = Some(run);
+        self.results = QueryResults::default();
+        self.columns.clear();
+        self.table_state = TableState::default();
+        Ok(())
+    }
+
+    /// Stop polling. The polling task issues StopQuery for the running query.
+    fn cancel_query(&mut self) {
+        if !self.is_running() {
+            return;
+        }
+        if let Some(run) = &mut self.run {
+            run.cancel_token.cancel();
+            run.finished_at = Some(Utc::now());
+            self.results.status = QueryStatus::Cancelled;
+        }
+    }
+
+    fn update_query(&mut self, update: &UpdateQuery) {
+        let Some(run) = self.run.as_mut().filter(|run| run.id == update.run_id) else {
+            return;
+        };
+        if run.finished_at.is_some() {
+            return;
+        }
+
+        match &update.update {
+            QueryUpdate::Results(results) => {
+                if results.status.is_finished() {
+                    run.finished_at = Some(Utc::now());
+                }
+                self.results = results.clone();
+                // 列はクエリのfieldsの順番で、結果に現れた順に並べる
+                self.columns.clear();
+                for row in &self.results.rows {
+                    for (name, _) in &row.fields {
+                        if !self.columns.contains(name) {
+                            self.columns.push(name.clone());
+                        }
+                    }
+                }
+            }
+            QueryUpdate::Failed => {
+                run.finished_at = Some(Utc::now());
+                self.results.status = QueryStatus::Failed;
+            }
+        }
+    }
+
+    fn select_row(&self, tx: &UnboundedSender<Action>) -> Result<()> {
+        if let Some(row) = self
+            .table_state
+            .selected()
+            .and_then(|index| self.results.rows.get(index))
+        {
+            tx.send(Action::ComponentAction(Box::new(SelectLog {
+                selected_log: row.message.clone(),
+            })))?;
+        }
+        Ok(())
+    }
+
+    fn status_line(&self) -> Line<'static> {
+        let Some(run) = &self.run else {
+            return Line::from("Ctrl-r: run, Ctrl-x: cancel, Tab: switch focus, Esc: close")
+                .style(Style::new().fg(Color::Gray));
+        };
+
+        let elapsed = run.finished_at.unwrap_or_else(Utc::now) - run.started_at;
+        let seconds = elapsed.num_milliseconds() as f64 / 1000.0;
+        let statistics = format!(
+            "{} rows, {:.0} matched / {:.0} scanned",
+            self.results.rows.len(),
+            self.results.records_matched,
+            self.results.records_scanned
+        );
+        if self.is_running() {
+            let spinner = SPINNER[(elapsed.num_milliseconds() / 250) as usize % SPINNER.len()];
+            Line::from(format!(
+                "{} {} {:.1}s - {}",
+                spinner, self.results.status, seconds, statistics
+            ))
+            .style(Style::new().fg(Color::Yellow))
+        } else {
+            let style = match self.results.status {
+                QueryStatus::Complete => Style::new().fg(Color::Green),
+                _ => Style::new().fg(Color::Red),
+            };
+            Line::from(format!(
+                "{} in {:.1}s - {}",
+                self.results.status, seconds, statistics
+            ))
+            .style(style)
+        }
+    }
+
+    fn draw_results(&mut self, frame: &mut Frame, area: Rect) {
+        let widths: Vec<Constraint> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                if index + 1 == self.columns.len() {
+                    return Constraint::Fill(1);
+                }
+                let width = self
+                    .results
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.fields.iter().find(|(name, _)| name == column))
+                    .map(|(_, value)| value.chars().count())
+                    .chain(std::iter::once(column.chars().count()))
+                    .max()
+                    .unwrap_or_default()
+                    .min(MAX_COLUMN_WIDTH);
+                Constraint::Length(width as u16)
+            })
+            .collect();
+        let rows = self.results.rows.iter().map(|row| {
+            Row::new(self.columns.iter().map(|column| {
+                row.fields
+                    .iter()
+                    .find(|(name, _)| name == column)
+                    .map(|(_, value)| value.replace('\n', " "))
+                    .unwrap_or_default()
+            }))
+        });
+
+        let border_style = if self.focus == Focus::Results {
+            Style::new().fg(Color::Yellow)
+        } else {
+            Style::new()
+        };
+        frame.render_stateful_widget(
+            Table::new(rows, widths)
+                .header(
+                    Row::new(self.columns.clone())
+                        .style(Style::new().bold())
+                        .bottom_margin(1),
+                )
+                .row_highlight_style(Style::new().reversed())
+                .highlight_symbol(">")
+                .block(
+                    Block::bordered()
+                        .border_style(border_style)
+                        .title("Results"),
+                ),
+            area,
+            &mut self.table_state,
+        );
+    }
+}
+
+/// Start the query and poll its results until it finishes or is cancelled.
+async fn poll_query(
+    backend: Arc<dyn LogBackend>,
+    request: StartQueryRequest,
+    run_id: Ulid,
+    tx: UnboundedSender<Action>,
+    cancel_token: CancellationToken,
+) {
+    let send = |update: QueryUpdate| {
+        tx.send(Action::ComponentAction(Box::new(UpdateQuery {
+            run_id,
+            update,
+        })))
+        .is_ok()
+    };
+
+    let query_id = match backend.start_query(request).await {
+        Ok(query_id) => query_id,
+        Err(e) => {
+            debug!("Failed to start query: {:?}", e);
+            let _ = tx.send(Action::Error(format!("Failed to start query: {e:#}")));
+            send(QueryUpdate::Failed);
+            return;
+        }
+    };
+    loop {
+        if cancel_token.is_cancelled() {
+            break;
+        }
+        match backend.get_query_results(query_id.clone()).await {
+            Ok(results) => {
+                let is_finished = results.status.is_finished();
+                if !send(QueryUpdate::Results(results)) || is_finished {
+                    return;
+                }
+            }
+            Err(e) => {
+                debug!("Failed to get query results: {:?}", e);
+                let _ = tx.send(Action::Error(format!("Failed to get query results: {e:#}")));
+                send(QueryUpdate::Failed);
+                return;
+            }
+        }
+
+        tokio::select! {
+            _ = cancel_token.cancelled() => break,
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+        }
+    }
+
+    debug!("Stopping query {}", query_id);
+    if let Err(e) = backend.stop_query(query_id).await {
+        let _ = tx.send(Action::Error(format!("Failed to stop query: {e:#}")));
+    }
+}
+
+impl Component for Insights {
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_key_event(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('r') => self.run_query(&tx)?,
+                KeyCode::Char('x') => self.cancel_query(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        match key.code {
+            KeyCode::Esc => return self.close(&tx),
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Query => Focus::TimeRange,
+                    Focus::TimeRange => Focus::Results,
+                    Focus::Results => Focus::Query,
+                };
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        match self.focus {
+            Focus::Query => self.query.handle_key_event(key),
+            Focus::TimeRange => {
+                if let InputOutcome::Submit(_) = self.time_range.handle_key_event(key) {
+                    self.run_query(&tx)?;
+                }
+            }
+            Focus::Results => match key.code {
+                KeyCode::Up | KeyCode::PageUp => {
+                    self.table_state.select_previous();
+                    self.select_row(&tx)?;
+                }
+                KeyCode::Down | KeyCode::PageDown => {
+                    self.table_state.select_next();
+                    self.select_row(&tx)?;
+                }
+                _ => {}
+            },
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action, _tx: UnboundedSender<Action>) -> Result<()> {
+        let Action::ComponentAction(action) = action else {
+            return Ok(());
+        };
+
+        if let Some(action) = action.as_any().downcast_ref::<UpdateQuery>() {
+            self.update_query(action);
+        } else if let Some(action) = action.as_any().downcast_ref::<SelectLogGroup>() {
+            self.log_group_identifiers = action
+                .log_groups
+                .iter()
+                .map(|log_group| log_group.arn.clone())
+                .collect();
+        } else if action.as_any().downcast_ref::<SelectProfile>().is_some() {
+            self.cancel_query();
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let query_height = (self.query.value().lines().count() as u16 + 3).clamp(5, 12);
+        let [query_area, time_range_area, status_area, results_area] = Layout::vertical([
+            Constraint::Length(query_height),
+            Constraint::Length(3),
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        let focused_style = |focus| {
+            if self.focus == focus {
+                Style::new().fg(Color::Yellow)
+            } else {
+                Style::new()
+            }
+        };
+        self.query.draw(
+            frame,
+            query_area,
+            Block::bordered()
+                .border_style(focused_style(Focus::Query))
+                .title(format!(
+                    "Logs Insights: {} log group(s)",
+                    self.log_group_identifiers.len()
+                )),
+            self.focus == Focus::Query,
+        );
+
+        if self.focus == Focus::TimeRange {
+            self.time_range.draw(
+                frame,
+                time_range_area,
+                "Time range, e.g. -1h or 2025-06-07 10:00..2025-06-07 11:00 (Enter: run)",
+            );
+        } else {
+            frame.render_widget(
+                Paragraph::new(self.time_range.value())
+                    .block(Block::bordered().title("Time range")),
+                time_range_area,
+            );
+        }
+
+        frame.render_widget(Paragraph::new(self.status_line()), status_area);
+        self.draw_results(frame, results_area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        backend::{QueryRow, fake::FakeLogBackend},
+        components::log_group_list::LogGroup,
+    };
+
+    fn select_log_group(insights: &mut Insights, tx: UnboundedSender<Action>) {
+        let select_action = SelectLogGroup {
+            log_groups: vec![LogGroup {
+                arn: "arn:aws:logs:ap-northeast-1:123456789012:log-group:foo".to_string(),
+                ..Default::default()
+            }],
+        };
+        insights
+            .update(Action::ComponentAction(Box::new(select_action)), tx)
+            .unwrap();
+    }
+
+    fn row(message: &str) -> QueryRow {
+        QueryRow::new(
+            Some("ap-northeast-1"),
+            vec![
+                (
+                    "@timestamp".to_string(),
+                    "2025-06-07 10:00:00.000".to_string(),
+                ),
+                ("@message".to_string(), message.to_string()),
+            ],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_run_query_until_complete() {
+        let backend = Arc::new(FakeLogBackend::new().query_results(vec![QueryResults {
+            status: QueryStatus::Complete,
+            records_matched: 1.0,
+            records_scanned: 10.0,
+            rows: vec![row("hello")],
+        }]));
+        let mut insights = Insights::new(backend.clone());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        select_log_group(&mut insights, tx.clone());
+
+        insights
+            .handle_key_event(
+                crossterm::event::KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+                tx.clone(),
+            )
+            .unwrap();
+        while insights.run.as_ref().unwrap().finished_at.is_none() {
+            let action = rx.recv().await.unwrap();
+            insights.update(action, tx.clone()).unwrap();
+        }
+
+        assert_eq!(insights.results.status, QueryStatus::Complete);
+        assert_eq!(insights.columns, vec!["@timestamp", "@message"]);
+        assert_eq!(
+            backend.start_query_requests()[0].query_string,
+            DEFAULT_QUERY
+        );
+
+        // 結果の行を選ぶとLogDetailに表示される
+        insights.focus = Focus::Results;
+        insights
+            .handle_key_event(KeyCode::Down.into(), tx.clone())
+            .unwrap();
+        let Some(Action::ComponentAction(action)) = rx.recv().await else {
+            panic!("expected SelectLog");
+        };
+        let select_log = action.as_any().downcast_ref::<SelectLog>().unwrap();
+        assert!(select_log.selected_log.content.contains("hello"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_query_stops_it() {
+        let backend = Arc::new(FakeLogBackend::new().query_results(vec![QueryResults {
+            status: QueryStatus::Running,
+            ..Default::default()
+        }]));
+        let mut insights = Insights::new(backend.clone());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        select_log_group(&mut insights, tx.clone());
+
+        insights.run_query(&tx).unwrap();
+        // 最初の結果を受け取るまで待つ
+        loop {
+            let action = rx.recv().await.unwrap();
+            insights.update(action, tx.clone()).unwrap();
+            if insights.results.status == QueryStatus::Running {
+                break;
+            }
+        }
+        insights
+            .handle_key_event(
+                crossterm::event::KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL),
+                tx.clone(),
+            )
+            .unwrap();
+        assert!(!insights.is_running());
+        assert_eq!(insights.results.status, QueryStatus::Cancelled);
+
+        for _ in 0..10 {
+            if !backend.stopped_queries().is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(backend.stopped_queries(), vec!["query-1".to_string()]);
+    }
+
+    #[test]
+    fn test_run_query_requires_log_groups() {
+        let mut insights = Insights::new(Arc::new(FakeLogBackend::new()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        insights.run_query(&tx).unwrap();
+        assert!(matches!(rx.try_recv(), Ok(Action::Error(_))));
+        assert!(insights.run.is_none());
+    }
+}
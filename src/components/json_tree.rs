@@ -0,0 +1,540 @@
+use std::collections::HashSet;
+
+use ratatui::{prelude::*, widgets::*};
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Object(Vec<(String, Node)>),
+    Array(Vec<Node>),
+    /// JSON decoded from a string value, e.g. the body of an API Gateway event
+    Embedded(Box<Node>),
+    Scalar(Value),
+}
+
+impl Node {
+    fn new(value: &Value) -> Self {
+        match value {
+            Value::Object(object) => Node::Object(
+                object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Node::new(value)))
+                    .collect(),
+            ),
+            Value::Array(array) => Node::Array(array.iter().map(Node::new).collect()),
+            Value::String(text) => match parse_embedded(text) {
+                Some(value) => Node::Embedded(Box::new(Node::new(&value))),
+                None => Node::Scalar(value.clone()),
+            },
+            value => Node::Scalar(value.clone()),
+        }
+    }
+
+    /// Embedded JSON is returned decoded.
+    fn to_value(&self) -> Value {
+        match self {
+            Node::Object(entries) => Value::Object(
+                entries
+                    .iter()
+                    .map(|(key, node)| (key.clone(), node.to_value()))
+                    .collect(),
+            ),
+            Node::Array(items) => Value::Array(items.iter().map(Node::to_value).collect()),
+            Node::Embedded(inner) => inner.to_value(),
+            Node::Scalar(value) => value.clone(),
+        }
+    }
+
+    fn child(&self, index: usize) -> Option<&Node> {
+        match self {
+            Node::Object(entries) => entries.get(index).map(|(_, node)| node),
+            Node::Array(items) => items.get(index),
+            Node::Embedded(inner) => inner.child(index),
+            Node::Scalar(_) => None,
+        }
+    }
+}
+
+/// Parse a string value holding a JSON object or array.
+fn parse_embedded(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if !(trimmed.starts_with('{') || trimmed.starts_with('[')) {
+        return None;
+    }
+    serde_json::from_str(trimmed)
+        .ok()
+        .filter(|value: &Value| value.is_object() || value.is_array())
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum RowKind {
+    Open {
+        bracket: char,
+        embedded: bool,
+        collapsed: bool,
+        len: usize,
+    },
+    Close(char),
+    Scalar(Value),
+}
+
+/// One line of the pretty-printed tree.
+#[derive(Clone, Debug, PartialEq)]
+struct Row {
+    depth: usize,
+    /// JSON path of the node, e.g. `$.event.key1`
+    path: String,
+    /// Indices of the children followed from the root to the node
+    indices: Vec<usize>,
+    key: Option<String>,
+    kind: RowKind,
+    comma: bool,
+}
+
+/// Pretty-printed JSON whose objects and arrays can be expanded and collapsed.
+#[derive(Clone, Debug, Default)]
+pub struct JsonTree {
+    root: Option<Node>,
+    collapsed: HashSet<String>,
+    rows: Vec<Row>,
+    list_state: ListState,
+}
+
+impl JsonTree {
+    /// Build a tree when the text is a JSON object or array.
+    pub fn parse(text: &str) -> Option<Self> {
+        let value = parse_embedded(text)?;
+        let mut tree = Self {
+            root: Some(Node::new(&value)),
+            ..Default::default()
+        };
+        tree.rebuild();
+        tree.list_state.select(Some(0));
+        Some(tree)
+    }
+
+    fn rebuild(&mut self) {
+        let mut rows = Vec::new();
+        if let Some(root) = &self.root {
+            let position = Position {
+                depth: 0,
+                path: "$".to_string(),
+                indices: Vec::new(),
+                key: None,
+                embedded: false,
+                comma: false,
+            };
+            flatten(root, position, &self.collapsed, &mut rows);
+        }
+        self.rows = rows;
+    }
+
+    fn selected_row(&self) -> Option<&Row> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.rows.get(index))
+    }
+
+    /// JSON path of the selected node.
+    pub fn selected_path(&self) -> Option<&str> {
+        self.selected_row().map(|row| row.path.as_str())
+    }
+
+    /// Value of the selected node. Strings are returned without quotes, objects and arrays
+    /// pretty-printed.
+    pub fn selected_value(&self) -> Option<String> {
+        let row = self.selected_row()?;
+        let node = row
+            .indices
+            .iter()
+            .try_fold(self.root.as_ref()?, |node, &index| node.child(index))?;
+        Some(match node.to_value() {
+            Value::String(text) => text,
+            value => serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string()),
+        })
+    }
+
+    fn set_collapsed(&mut self, path: String, collapsed: bool) {
+        if collapsed {
+            self.collapsed.insert(path.clone());
+        } else {
+            self.collapsed.remove(&path);
+        }
+        self.rebuild();
+        // 開閉したノードの開き括弧の行にカーソルを合わせる
+        let index = self.rows.iter().position(|row| row.path == path);
+        self.list_state.select(index);
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(row) = self.selected_row()
+            && matches!(row.kind, RowKind::Open { .. } | RowKind::Close(_))
+        {
+            let path = row.path.clone();
+            let collapsed = !self.collapsed.contains(&path);
+            self.set_collapsed(path, collapsed);
+        }
+    }
+
+    pub fn expand(&mut self) {
+        if let Some(row) = self.selected_row()
+            && let RowKind::Open {
+                collapsed: true, ..
+            } = row.kind
+        {
+            self.set_collapsed(row.path.clone(), false);
+        }
+    }
+
+    /// Collapse the selected node, or move to the parent when it's already collapsed or a value.
+    pub fn collapse(&mut self) {
+        let Some(index) = self.list_state.selected() else {
+            return;
+        };
+        let Some(row) = self.rows.get(index) else {
+            return;
+        };
+        if let RowKind::Open {
+            collapsed: false, ..
+        } = row.kind
+        {
+            self.set_collapsed(row.path.clone(), true);
+            return;
+        }
+
+        let depth = row.depth;
+        let parent = self.rows[..index]
+            .iter()
+            .rposition(|row| row.depth + 1 == depth && matches!(row.kind, RowKind::Open { .. }));
+        if parent.is_some() {
+            self.list_state.select(parent);
+        }
+    }
+
+    pub fn expand_all(&mut self) {
+        self.collapsed.clear();
+        self.rebuild();
+        self.list_state.select(Some(0));
+    }
+
+    /// Collapse every node below the root.
+    pub fn collapse_all(&mut self) {
+        self.collapsed = self
+            .rows
+            .iter()
+            .filter(|row| row.depth > 0 && matches!(row.kind, RowKind::Open { .. }))
+            .map(|row| row.path.clone())
+            .collect();
+        self.rebuild();
+        self.list_state.select(Some(0));
+    }
+
+    pub fn select_next(&mut self) {
+        self.list_state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.list_state.select_previous();
+    }
+
+    pub fn scroll_down_by(&mut self, amount: u16) {
+        self.list_state.scroll_down_by(amount);
+    }
+
+    pub fn scroll_up_by(&mut self, amount: u16) {
+        self.list_state.scroll_up_by(amount);
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect, block: Block) {
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| ListItem::new(row_line(row)))
+            .collect();
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::new().reversed())
+                .block(block),
+            area,
+            &mut self.list_state,
+        );
+    }
+}
+
+/// Where a node is printed in the tree.
+struct Position {
+    depth: usize,
+    path: String,
+    indices: Vec<usize>,
+    key: Option<String>,
+    embedded: bool,
+    comma: bool,
+}
+
+fn flatten(node: &Node, position: Position, collapsed: &HashSet<String>, rows: &mut Vec<Row>) {
+    let Position {
+        depth,
+        path,
+        indices,
+        key,
+        embedded,
+        comma,
+    } = position;
+    let child = |index: usize, len: usize, path: String, key: Option<String>| Position {
+        depth: depth + 1,
+        path,
+        indices: [indices.as_slice(), &[index]].concat(),
+        key,
+        embedded: false,
+        comma: index + 1 < len,
+    };
+    let (bracket, children): (char, Vec<(Position, &Node)>) = match node {
+        Node::Embedded(inner) => {
+            let position = Position {
+                depth,
+                path,
+                indices,
+                key,
+                embedded: true,
+                comma,
+            };
+            return flatten(inner, position, collapsed, rows);
+        }
+        Node::Scalar(value) => {
+            rows.push(Row {
+                depth,
+                path,
+                indices,
+                key,
+                kind: RowKind::Scalar(value.clone()),
+                comma,
+            });
+            return;
+        }
+        Node::Object(entries) => (
+            '{',
+            entries
+                .iter()
+                .enumerate()
+                .map(|(index, (child_key, node))| {
+                    let child_path = child_path(&path, child_key);
+                    let position = child(index, entries.len(), child_path, Some(child_key.clone()));
+                    (position, node)
+                })
+                .collect(),
+        ),
+        Node::Array(items) => (
+            '[',
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, node)| {
+                    let position = child(index, items.len(), format!("{path}[{index}]"), None);
+                    (position, node)
+                })
+                .collect(),
+        ),
+    };
+
+    let is_collapsed = collapsed.contains(&path);
+    rows.push(Row {
+        depth,
+        path: path.clone(),
+        indices: indices.clone(),
+        key,
+        kind: RowKind::Open {
+            bracket,
+            embedded,
+            collapsed: is_collapsed,
+            len: children.len(),
+        },
+        comma: is_collapsed && comma,
+    });
+    if is_collapsed {
+        return;
+    }
+
+    for (position, node) in children {
+        flatten(node, position, collapsed, rows);
+    }
+    rows.push(Row {
+        depth,
+        path,
+        indices,
+        key: None,
+        kind: RowKind::Close(if bracket == '{' { '}' } else { ']' }),
+        comma,
+    });
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        format!("{path}.{key}")
+    } else {
+        format!("{path}[{}]", serde_json::to_string(key).unwrap_or_default())
+    }
+}
+
+fn bracket_style(depth: usize) -> Style {
+    let colors = [
+        Color::LightRed,
+        Color::LightBlue,
+        Color::LightCyan,
+        Color::LightMagenta,
+        Color::LightGreen,
+    ];
+    Style::new().fg(colors[depth % colors.len()])
+}
+
+fn row_line(row: &Row) -> Line<'static> {
+    let mut spans = vec![Span::raw("  ".repeat(row.depth))];
+    if let Some(key) = &row.key {
+        spans.push(Span::styled(
+            format!("{}: ", serde_json::to_string(key).unwrap_or_default()),
+            Style::new().fg(Color::LightBlue),
+        ));
+    }
+
+    match &row.kind {
+        RowKind::Open {
+            bracket,
+            embedded,
+            collapsed,
+            len,
+        } => {
+            if *embedded {
+                spans.push(Span::styled(
+                    "(json) ",
+                    Style::new().fg(Color::DarkGray).italic(),
+                ));
+            }
+            if *collapsed {
+                let close = if *bracket == '{' { '}' } else { ']' };
+                spans.push(Span::styled(
+                    format!("{bracket}…{close}"),
+                    bracket_style(row.depth),
+                ));
+                let unit = match (*bracket, *len) {
+                    ('{', 1) => "key",
+                    ('{', _) => "keys",
+                    (_, 1) => "item",
+                    _ => "items",
+                };
+                spans.push(Span::styled(
+                    format!(" {len} {unit}"),
+                    Style::new().fg(Color::DarkGray),
+                ));
+            } else {
+                spans.push(Span::styled(bracket.to_string(), bracket_style(row.depth)));
+            }
+        }
+        RowKind::Close(bracket) => {
+            spans.push(Span::styled(bracket.to_string(), bracket_style(row.depth)));
+        }
+        RowKind::Scalar(value) => {
+            let style = match value {
+                Value::String(_) => Style::new().fg(Color::Green),
+                Value::Number(_) => Style::new().fg(Color::Yellow),
+                Value::Bool(_) => Style::new().fg(Color::Magenta),
+                _ => Style::new().fg(Color::DarkGray),
+            };
+            spans.push(Span::styled(value.to_string(), style));
+        }
+    }
+    if row.comma {
+        spans.push(Span::raw(","));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lines(tree: &JsonTree) -> Vec<String> {
+        tree.rows
+            .iter()
+            .map(|row| {
+                row_line(row)
+                    .spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pretty_print_with_embedded_json() {
+        let tree = JsonTree::parse(
+            r#"{"level":"INFO","body":"{\"items\":[1,true]}","event":{"key 1":null}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            lines(&tree),
+            vec![
+                "{",
+                r#"  "body": (json) {"#,
+                r#"    "items": ["#,
+                "      1,",
+                "      true",
+                "    ]",
+                "  },",
+                r#"  "event": {"#,
+                r#"    "key 1": null"#,
+                "  },",
+                r#"  "level": "INFO""#,
+                "}",
+            ]
+        );
+        assert_eq!(tree.rows[3].path, "$.body.items[0]");
+        assert_eq!(tree.rows[3].indices, vec![0, 0, 0]);
+        assert_eq!(tree.rows[8].path, r#"$.event["key 1"]"#);
+        assert!(JsonTree::parse("plain text").is_none());
+        assert!(JsonTree::parse("\"string\"").is_none());
+    }
+
+    #[test]
+    fn test_selected_value() {
+        let mut tree =
+            JsonTree::parse(r#"{"body":"{\"id\":1}","message":"hello \"world\""}"#).unwrap();
+        assert_eq!(
+            tree.selected_value().unwrap(),
+            "{\n  \"body\": {\n    \"id\": 1\n  },\n  \"message\": \"hello \\\"world\\\"\"\n}"
+        );
+        tree.select_next();
+        assert_eq!(tree.selected_value().unwrap(), "{\n  \"id\": 1\n}");
+        tree.select_next();
+        assert_eq!(tree.selected_value().unwrap(), "1");
+        tree.select_next();
+        tree.select_next();
+        assert_eq!(tree.selected_path(), Some("$.message"));
+        assert_eq!(tree.selected_value().unwrap(), "hello \"world\"");
+    }
+
+    #[test]
+    fn test_expand_and_collapse() {
+        let mut tree = JsonTree::parse(r#"{"a":{"b":1,"c":[2]},"d":3}"#).unwrap();
+        tree.select_next();
+        assert_eq!(tree.selected_path(), Some("$.a"));
+
+        tree.collapse();
+        assert_eq!(lines(&tree)[1], r#"  "a": {…} 2 keys,"#);
+        assert_eq!(tree.rows.len(), 4);
+        tree.expand();
+        assert_eq!(tree.rows.len(), 9);
+
+        // 値の行では親に移動する
+        tree.select_next();
+        assert_eq!(tree.selected_path(), Some("$.a.b"));
+        tree.collapse();
+        assert_eq!(tree.selected_path(), Some("$.a"));
+
+        tree.collapse_all();
+        assert_eq!(lines(&tree).len(), 4);
+        tree.toggle();
+        assert_eq!(tree.rows.len(), 1);
+        tree.expand_all();
+        assert_eq!(tree.rows.len(), 9);
+    }
+}
@@ -1,34 +1,201 @@
 use color_eyre::Result;
 use ratatui::{
     prelude::*,
-    widgets::{Block, Paragraph, Wrap},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
+use serde_json::Value;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
     action::Action,
+    clipboard::copy_to_clipboard,
     components::{
+        json_tree::JsonTree,
         log_stream::{SelectLog, convert_to_line},
         outer_layout::Message,
     },
+    config::Config,
+    notification::show_notification,
 };
 
+/// What the copy menu copies to the clipboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CopyTarget {
+    /// Value of the node selected in the JSON tree
+    Value,
+    /// JSON path of the node selected in the JSON tree
+    Path,
+    Pretty,
+    Raw,
+}
+
+impl CopyTarget {
+    fn label(self) -> &'static str {
+        match self {
+            CopyTarget::Value => "field value",
+            CopyTarget::Path => "JSON path",
+            CopyTarget::Pretty => "pretty-printed message",
+            CopyTarget::Raw => "raw line",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct MessageAndLine<'a> {
     message: Message,
     content_line: Line<'a>,
+    /// Interactive view of JSON logs
+    json_tree: Option<JsonTree>,
 }
 
 #[derive(Default, Clone, Debug)]
 pub struct LogDetail<'a> {
+    config: Config,
     message_and_line: Option<MessageAndLine<'a>>,
+    /// Open while choosing what to copy
+    copy_menu: Option<ListState>,
 }
 
 impl<'a> LogDetail<'a> {
     pub fn new() -> Self {
         Self {
+            config: Config::default(),
             message_and_line: None,
+            copy_menu: None,
+        }
+    }
+
+    /// The copy menu takes every key while open.
+    pub fn is_copying(&self) -> bool {
+        self.copy_menu.is_some()
+    }
+
+    fn copy_targets(&self) -> Vec<CopyTarget> {
+        let Some(message_and_line) = &self.message_and_line else {
+            return Vec::new();
+        };
+        match &message_and_line.json_tree {
+            Some(_) => vec![
+                CopyTarget::Value,
+                CopyTarget::Path,
+                CopyTarget::Pretty,
+                CopyTarget::Raw,
+            ],
+            None => vec![CopyTarget::Raw],
+        }
+    }
+
+    fn copy_text(&self, target: CopyTarget) -> Option<String> {
+        let message_and_line = self.message_and_line.as_ref()?;
+        let json_tree = message_and_line.json_tree.as_ref();
+        match target {
+            CopyTarget::Value => json_tree?.selected_value(),
+            CopyTarget::Path => json_tree?.selected_path().map(str::to_string),
+            CopyTarget::Pretty => {
+                let content = &message_and_line.message.content;
+                match serde_json::from_str::<Value>(content) {
+                    Ok(value) => serde_json::to_string_pretty(&value).ok(),
+                    Err(_) => Some(content.clone()),
+                }
+            }
+            CopyTarget::Raw => Some(message_and_line.message.content.clone()),
+        }
+    }
+
+    fn copy(&mut self, target: CopyTarget, tx: &UnboundedSender<Action>) -> Result<()> {
+        self.copy_menu = None;
+        let Some(text) = self.copy_text(target) else {
+            return Ok(());
+        };
+        match copy_to_clipboard(&text, self.config.config.clipboard_command.as_deref()) {
+            Ok(()) => show_notification(
+                self.config.config.notification,
+                tx,
+                "Copy",
+                &format!("Copied {} to clipboard", target.label()),
+            ),
+            Err(e) => tx.send(Action::Error(format!("Failed to copy to clipboard: {e:#}")))?,
         }
+        Ok(())
+    }
+
+    fn handle_copy_menu_key(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        let targets = self.copy_targets();
+        let Some(list_state) = &mut self.copy_menu else {
+            return Ok(());
+        };
+        match key.code {
+            crossterm::event::KeyCode::Up => list_state.select_previous(),
+            crossterm::event::KeyCode::Down => list_state.select_next(),
+            crossterm::event::KeyCode::Enter => {
+                if let Some(&target) = list_state.selected().and_then(|index| targets.get(index)) {
+                    self.copy(target, &tx)?;
+                }
+            }
+            crossterm::event::KeyCode::Char(c @ '1'..='9') => {
+                if let Some(&target) = targets.get(c as usize - '1' as usize) {
+                    self.copy(target, &tx)?;
+                }
+            }
+            crossterm::event::KeyCode::Esc | crossterm::event::KeyCode::Char('c') => {
+                self.copy_menu = None;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw_copy_menu(&mut self, frame: &mut Frame, area: Rect) {
+        let targets = self.copy_targets();
+        let path = self
+            .message_and_line
+            .as_ref()
+            .and_then(|message_and_line| message_and_line.json_tree.as_ref())
+            .and_then(|json_tree| json_tree.selected_path())
+            .unwrap_or_default()
+            .to_string();
+        let Some(list_state) = &mut self.copy_menu else {
+            return;
+        };
+
+        let items: Vec<ListItem> = targets
+            .iter()
+            .enumerate()
+            .map(|(index, target)| {
+                let label = match target {
+                    CopyTarget::Value | CopyTarget::Path => {
+                        format!("{} {} ({})", index + 1, target.label(), path)
+                    }
+                    _ => format!("{} {}", index + 1, target.label()),
+                };
+                ListItem::new(label)
+            })
+            .collect();
+        let width = area.width.min(50);
+        let height = (items.len() as u16 + 2).min(area.height);
+        let menu_area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, menu_area);
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::new().reversed())
+                .block(Block::bordered().title("Copy (Enter: copy, Esc: close)")),
+            menu_area,
+            list_state,
+        );
+    }
+
+    pub fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
     }
 
     pub fn update(&mut self, action: Action, _tx: UnboundedSender<Action>) -> Result<()> {
@@ -37,8 +204,10 @@ impl<'a> LogDetail<'a> {
                 if let Some(select_log_action) =
                     component_action.as_any().downcast_ref::<SelectLog>()
                 {
+                    self.copy_menu = None;
                     self.message_and_line = Some(MessageAndLine {
                         content_line: convert_to_line(&select_log_action.selected_log.content),
+                        json_tree: JsonTree::parse(&select_log_action.selected_log.content),
                         message: select_log_action.selected_log.clone(),
                     });
                 }
@@ -48,8 +217,46 @@ impl<'a> LogDetail<'a> {
         }
     }
 
-    pub fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        if let Some(message_and_line) = &self.message_and_line {
+    pub fn handle_key_event(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        if self.copy_menu.is_some() {
+            return self.handle_copy_menu_key(key, tx);
+        }
+        if key.code == crossterm::event::KeyCode::Char('c') && self.message_and_line.is_some() {
+            self.copy_menu = Some(ListState::default().with_selected(Some(0)));
+            return Ok(());
+        }
+
+        let Some(json_tree) = self
+            .message_and_line
+            .as_mut()
+            .and_then(|message_and_line| message_and_line.json_tree.as_mut())
+        else {
+            return Ok(());
+        };
+
+        match key.code {
+            crossterm::event::KeyCode::Up => json_tree.select_previous(),
+            crossterm::event::KeyCode::Down => json_tree.select_next(),
+            crossterm::event::KeyCode::PageUp => json_tree.scroll_up_by(10),
+            crossterm::event::KeyCode::PageDown => json_tree.scroll_down_by(10),
+            crossterm::event::KeyCode::Enter | crossterm::event::KeyCode::Char(' ') => {
+                json_tree.toggle()
+            }
+            crossterm::event::KeyCode::Right => json_tree.expand(),
+            crossterm::event::KeyCode::Left => json_tree.collapse(),
+            crossterm::event::KeyCode::Char('+') => json_tree.expand_all(),
+            crossterm::event::KeyCode::Char('-') => json_tree.collapse_all(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect, focused: bool) -> Result<()> {
+        if let Some(message_and_line) = &mut self.message_and_line {
             // Create vertical layout: datetime, url, content
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -62,8 +269,14 @@ impl<'a> LogDetail<'a> {
 
             // Draw datetime
             frame.render_widget(
-                Paragraph::new(format!("DateTime: {}", message_and_line.message.datetime))
-                    .style(Style::default().fg(Color::Cyan)),
+                Paragraph::new(format!(
+                    "DateTime: {}",
+                    message_and_line
+                        .message
+                        .datetime
+                        .with_timezone(&self.config.config.timezone)
+                ))
+                .style(Style::default().fg(Color::Cyan)),
                 chunks[0],
             );
 
@@ -75,14 +288,139 @@ impl<'a> LogDetail<'a> {
             );
 
             // Draw content
-            frame.render_widget(
-                Paragraph::new(message_and_line.content_line.clone())
-                    .wrap(Wrap { trim: true })
-                    .block(Block::bordered().title("Log Content")),
-                chunks[2],
-            );
+            let block = if focused {
+                let path = message_and_line
+                    .json_tree
+                    .as_ref()
+                    .and_then(|json_tree| json_tree.selected_path())
+                    .unwrap_or_default();
+                Block::bordered()
+                    .border_style(Style::new().fg(Color::Cyan))
+                    .title(format!(
+                        "Log Content {path} (Enter: expand/collapse, +/-: all, c: copy)"
+                    ))
+            } else {
+                Block::bordered().title("Log Content")
+            };
+            match &mut message_and_line.json_tree {
+                Some(json_tree) => json_tree.draw(frame, chunks[2], block),
+                None => frame.render_widget(
+                    Paragraph::new(message_and_line.content_line.clone())
+                        .wrap(Wrap { trim: true })
+                        .block(block),
+                    chunks[2],
+                ),
+            }
+            let content_area = chunks[2];
+            self.draw_copy_menu(frame, content_area);
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::outer_layout::message;
+
+    #[test]
+    fn test_browse_json_tree() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_detail = LogDetail::new();
+        let selected_log = message(r#"{"event":{"key1":"value1"},"level":"INFO"}"#);
+        log_detail
+            .update(
+                Action::ComponentAction(Box::new(SelectLog { selected_log })),
+                tx.clone(),
+            )
+            .unwrap();
+
+        for code in [
+            crossterm::event::KeyCode::Down,
+            crossterm::event::KeyCode::Enter,
+            crossterm::event::KeyCode::Down,
+        ] {
+            log_detail
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        let json_tree = log_detail
+            .message_and_line
+            .as_ref()
+            .and_then(|message_and_line| message_and_line.json_tree.as_ref())
+            .unwrap();
+        assert_eq!(json_tree.selected_path(), Some("$.level"));
+    }
+
+    #[test]
+    fn test_copy_menu() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_detail = LogDetail::new();
+        let content = r#"{"event":{"key1":"value1"},"level":"INFO"}"#;
+        let selected_log = message(content);
+        log_detail
+            .update(
+                Action::ComponentAction(Box::new(SelectLog { selected_log })),
+                tx.clone(),
+            )
+            .unwrap();
+
+        for code in [
+            crossterm::event::KeyCode::Down,
+            crossterm::event::KeyCode::Down,
+        ] {
+            log_detail
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        assert_eq!(
+            log_detail.copy_text(CopyTarget::Value).as_deref(),
+            Some("value1")
+        );
+        assert_eq!(
+            log_detail.copy_text(CopyTarget::Path).as_deref(),
+            Some("$.event.key1")
+        );
+        assert_eq!(
+            log_detail.copy_text(CopyTarget::Pretty),
+            Some(
+                serde_json::to_string_pretty(&serde_json::from_str::<Value>(content).unwrap())
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            log_detail.copy_text(CopyTarget::Raw).as_deref(),
+            Some(content)
+        );
+
+        // メニューを開いている間は矢印キーでツリーが動かない
+        log_detail
+            .handle_key_event(crossterm::event::KeyCode::Char('c').into(), tx.clone())
+            .unwrap();
+        assert!(log_detail.copy_menu.is_some());
+        log_detail
+            .handle_key_event(crossterm::event::KeyCode::Down.into(), tx.clone())
+            .unwrap();
+        assert_eq!(log_detail.copy_menu.as_ref().unwrap().selected(), Some(1));
+        log_detail
+            .handle_key_event(crossterm::event::KeyCode::Esc.into(), tx.clone())
+            .unwrap();
+        assert!(log_detail.copy_menu.is_none());
+        assert_eq!(
+            log_detail.copy_text(CopyTarget::Path).as_deref(),
+            Some("$.event.key1")
+        );
+
+        let plain_log = message("START RequestId: abc");
+        log_detail
+            .update(
+                Action::ComponentAction(Box::new(SelectLog {
+                    selected_log: plain_log,
+                })),
+                tx,
+            )
+            .unwrap();
+        assert_eq!(log_detail.copy_targets(), vec![CopyTarget::Raw]);
+    }
+}
@@ -1,17 +1,18 @@
 use std::collections::HashSet;
 use std::env;
 use std::fs;
+use std::sync::Arc;
 
 use chrono::{DateTime, Utc};
-use chrono_tz::{Asia::Tokyo, Tz};
 use color_eyre::Result;
 use ratatui::{prelude::*, widgets::*};
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::debug;
 
-use super::Component;
+use super::{Component, profile_picker::SelectProfile};
 use crate::{
     action::{Action, ComponentAction},
+    backend::LogBackend,
     config::Config,
     date::get_diff,
 };
@@ -20,7 +21,7 @@ use crate::{
 pub struct LogGroup {
     pub name: String,
     pub arn: String,
-    creation_time: DateTime<Tz>,
+    pub creation_time: DateTime<Utc>,
 }
 
 impl Default for LogGroup {
@@ -28,7 +29,7 @@ impl Default for LogGroup {
         Self {
             name: String::new(),
             arn: String::new(),
-            creation_time: Utc::now().with_timezone(&Tokyo),
+            creation_time: Utc::now(),
         }
     }
 }
@@ -69,39 +70,47 @@ impl ComponentAction for SelectLogGroup {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct LogGroupList {
+    backend: Arc<dyn LogBackend>,
     loaded_log_groups: Vec<LogGroup>,
     table_state: TableState,
 
     selected_log_groups: HashSet<usize>,
 }
 
-impl Default for LogGroupList {
-    fn default() -> Self {
-        let mut logs = Vec::new();
-
-        (0..100).for_each(|i| {
-            logs.push(LogGroup {
-                creation_time: Utc::now().with_timezone(&Tokyo)
-                    - std::time::Duration::from_secs(i as u64 * 60), // Simulate creation time
-                name: format!("LogGroup{}", i),
-                arn: format!(
-                    "arn:aws:logs:us-west-2:123456789012:log-group:LogGroup{}",
-                    i
-                ),
-            });
-        });
-
+impl LogGroupList {
+    pub fn new(backend: Arc<dyn LogBackend>) -> Self {
         Self {
-            loaded_log_groups: logs,
-            selected_log_groups: HashSet::new(),
+            backend,
+            loaded_log_groups: Vec::new(),
             table_state: TableState::default(),
+            selected_log_groups: HashSet::new(),
         }
     }
-}
 
-impl LogGroupList {
+    fn fetch_log_groups(&self, tx: UnboundedSender<Action>) {
+        let backend = self.backend.clone();
+
+        tokio::spawn(async move {
+            let log_groups = match backend.list_log_groups().await {
+                Ok(log_groups) => log_groups,
+                Err(e) => {
+                    debug!("Failed to list log groups: {}", e);
+                    let _ = tx.send(Action::Error(format!("Failed to list log groups: {e:#}")));
+                    return;
+                }
+            };
+
+            tx.send(Action::ComponentAction(Box::new(FetchLogGroups {
+                log_groups,
+            })))
+            .unwrap_or_else(|e| {
+                debug!("Failed to send FetchLogGroups action: {}", e);
+            })
+        });
+    }
+
     fn save_selected_log_groups(&self) {
         let temp_dir = env::temp_dir();
         let filepath = temp_dir.join("cwlogs-viewer-selected-groups.txt");
@@ -157,49 +166,7 @@ impl LogGroupList {
 
 impl Component for LogGroupList {
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
-        tokio::spawn(async move {
-            // Initialize AWS SDK
-            debug!("Initializing AWS SDK for CloudWatch Logs");
-            let config = aws_config::load_from_env().await;
-            let client = aws_sdk_cloudwatchlogs::Client::new(&config);
-
-            let mut log_groups: Vec<LogGroup> = client
-                .describe_log_groups()
-                .into_paginator()
-                .send()
-                .try_collect()
-                .await
-                .map_err(|e| {
-                    debug!("Failed to list log groups: {}", e);
-                    e
-                })
-                .into_iter()
-                .flat_map(|res| res.into_iter().flat_map(|group| group.log_groups.unwrap()))
-                .map(|log_group| LogGroup {
-                    creation_time: DateTime::from_timestamp_millis(
-                        log_group.creation_time.unwrap(),
-                    )
-                    .unwrap()
-                    .with_timezone(&chrono_tz::Asia::Tokyo),
-                    name: log_group.log_group_name.unwrap_or_default(),
-                    arn: log_group.log_group_arn.unwrap_or_default(),
-                })
-                .collect();
-
-            log_groups.sort_by(|a, b| {
-                b.creation_time
-                    .partial_cmp(&a.creation_time)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-
-            tx.send(Action::ComponentAction(Box::new(FetchLogGroups {
-                log_groups,
-            })))
-            .unwrap_or_else(|e| {
-                debug!("Failed to send FetchLogGroups action: {}", e);
-            })
-        });
-
+        self.fetch_log_groups(tx);
         Ok(())
     }
 
@@ -254,6 +221,18 @@ impl Component for LogGroupList {
     fn update(&mut self, action: Action, tx: UnboundedSender<Action>) -> Result<()> {
         match action {
             Action::ComponentAction(component_action) => {
+                if component_action
+                    .as_any()
+                    .downcast_ref::<SelectProfile>()
+                    .is_some()
+                {
+                    // 別アカウントのロググループを読み直す
+                    self.loaded_log_groups.clear();
+                    self.selected_log_groups.clear();
+                    self.table_state = TableState::default();
+                    self.fetch_log_groups(tx.clone());
+                }
+
                 if let Some(fetch_action) =
                     component_action.as_any().downcast_ref::<FetchLogGroups>()
                 {
@@ -323,16 +302,62 @@ impl Component for LogGroupList {
 
 #[cfg(test)]
 mod test {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::backend::fake::FakeLogBackend;
 
-    // #[test]
-    // fn test_creation_time() {
-    //     // 1433189500783ミリ秒 = 2015-06-02T05:11:40.783Z
-    //     let timestamp_ms = 1433189500783;
-    //     let system_time = SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(timestamp_ms);
-
-    //     // JST形式（日本標準時）で出力
-    //     let utc_time: DateTime<Utc> = system_time.into();
-    //     let jst_time = utc_time.with_timezone(&Tokyo);
-    //     dbg!(jst_time.to_rfc3339());
-    // }
+    #[tokio::test]
+    async fn test_fetch_log_groups_from_backend() {
+        let log_group = LogGroup {
+            name: "/aws/lambda/foo".to_string(),
+            arn: "arn:aws:logs:ap-northeast-1:123456789012:log-group:/aws/lambda/foo".to_string(),
+            ..Default::default()
+        };
+        let backend = Arc::new(FakeLogBackend::new().log_groups(vec![log_group.clone()]));
+        let mut log_group_list = LogGroupList::new(backend);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        log_group_list.register_action_handler(tx).unwrap();
+
+        let Some(Action::ComponentAction(action)) = rx.recv().await else {
+            panic!("expected FetchLogGroups");
+        };
+        let fetch_action = action.as_any().downcast_ref::<FetchLogGroups>().unwrap();
+        assert_eq!(fetch_action.log_groups, vec![log_group]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_failure_is_reported() {
+        let backend = Arc::new(FakeLogBackend::new().log_groups_error("AccessDeniedException"));
+        let mut log_group_list = LogGroupList::new(backend);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        log_group_list.register_action_handler(tx).unwrap();
+
+        let Some(Action::Error(message)) = rx.recv().await else {
+            panic!("expected Error");
+        };
+        assert!(message.contains("AccessDeniedException"));
+    }
+
+    #[tokio::test]
+    async fn test_select_profile_refetches_log_groups() {
+        let backend = Arc::new(FakeLogBackend::new().log_groups(vec![LogGroup::default()]));
+        let mut log_group_list = LogGroupList::new(backend);
+        log_group_list.selected_log_groups.insert(3);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let select_profile = SelectProfile {
+            profile: "prod".to_string(),
+        };
+        log_group_list
+            .update(Action::ComponentAction(Box::new(select_profile)), tx)
+            .unwrap();
+        assert!(log_group_list.selected_log_groups.is_empty());
+
+        let Some(Action::ComponentAction(action)) = rx.recv().await else {
+            panic!("expected FetchLogGroups");
+        };
+        assert!(action.as_any().downcast_ref::<FetchLogGroups>().is_some());
+    }
 }
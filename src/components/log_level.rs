@@ -0,0 +1,173 @@
+use serde_json::Value;
+
+/// Severity detected from the content of a log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+/// JSON fields holding the level, e.g. `level` of pino or Lambda's JSON format,
+/// `severity` of Cloud Logging style loggers and `levelname` of Python's logging.
+const LEVEL_FIELDS: [&str; 3] = ["level", "severity", "levelname"];
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+
+    /// Key of the style in the `Styles` config, e.g. `level.error`.
+    pub fn style_key(self) -> &'static str {
+        match self {
+            LogLevel::Error => "level.error",
+            LogLevel::Warn => "level.warn",
+            LogLevel::Info => "level.info",
+            LogLevel::Debug => "level.debug",
+            LogLevel::Trace => "level.trace",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "fatal" | "critical" | "crit" | "panic" | "alert" | "emerg" | "emergency" | "error"
+            | "err" => Some(LogLevel::Error),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "info" | "notice" | "information" => Some(LogLevel::Info),
+            "debug" => Some(LogLevel::Debug),
+            "trace" | "verbose" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+
+    /// Numeric levels of pino and bunyan.
+    fn from_number(number: u64) -> Option<Self> {
+        match number {
+            0..=19 => Some(LogLevel::Trace),
+            20..=29 => Some(LogLevel::Debug),
+            30..=39 => Some(LogLevel::Info),
+            40..=49 => Some(LogLevel::Warn),
+            _ => Some(LogLevel::Error),
+        }
+    }
+
+    /// Detect the level from JSON fields, a Lambda `[ERROR]` prefix or a keyword in plain text.
+    ///
+    /// `json` is the content already parsed as JSON, if it is.
+    pub fn detect(content: &str, json: Option<&Value>) -> Option<Self> {
+        if let Some(Value::Object(object)) = json {
+            return object
+                .iter()
+                .find(|(key, _)| {
+                    LEVEL_FIELDS
+                        .iter()
+                        .any(|field| key.eq_ignore_ascii_case(field))
+                })
+                .and_then(|(_, value)| match value {
+                    Value::String(name) => Self::from_name(name),
+                    Value::Number(number) => number.as_u64().and_then(Self::from_number),
+                    _ => None,
+                });
+        }
+
+        // Pythonランタイムの`[ERROR]\t2025-06-07T...`形式
+        if let Some(rest) = content.trim_start().strip_prefix('[')
+            && let Some((name, _)) = rest.split_once(']')
+            && let Some(level) = Self::from_name(name)
+        {
+            return Some(level);
+        }
+
+        // Node.jsランタイムの`2025-06-07T...\t<request id>\tERROR\t...`形式
+        if let Some(level) = content
+            .split('\t')
+            .take(4)
+            .find_map(|field| Self::from_name(field.trim()))
+        {
+            return Some(level);
+        }
+
+        // 平文では大文字のキーワードと例外だけを見る
+        content
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .find_map(|word| match word {
+                "Exception" | "Traceback" => Some(LogLevel::Error),
+                word if word.len() > 2 && word == word.to_ascii_uppercase() => {
+                    Self::from_name(word)
+                }
+                _ => None,
+            })
+    }
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn detect(content: &str) -> Option<LogLevel> {
+        LogLevel::detect(content, serde_json::from_str(content).ok().as_ref())
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            detect(r#"{"level":"warning","message":"slow"}"#),
+            Some(LogLevel::Warn)
+        );
+        assert_eq!(
+            detect(r#"{"severity":"ERROR","message":"failed"}"#),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            detect(r#"{"levelname":"DEBUG","msg":"query"}"#),
+            Some(LogLevel::Debug)
+        );
+        assert_eq!(
+            detect(r#"{"level":30,"msg":"listening"}"#),
+            Some(LogLevel::Info)
+        );
+        // JSONにレベルが無い場合は本文のキーワードを見ない
+        assert_eq!(detect(r#"{"message":"ERROR"}"#), None);
+
+        assert_eq!(
+            detect("[ERROR]\t2025-06-07T10:00:00.000Z\tabc\tfailed"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            detect("2025-06-07T10:00:00.000Z\tabc\tINFO\thello"),
+            Some(LogLevel::Info)
+        );
+        assert_eq!(
+            detect("java.lang.IllegalStateException: WARN ignored"),
+            Some(LogLevel::Warn)
+        );
+        assert_eq!(
+            detect("Unhandled Exception in handler"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(detect("an error occurred"), None);
+        assert_eq!(detect("START RequestId: abc Version: $LATEST"), None);
+    }
+}
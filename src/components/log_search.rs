@@ -0,0 +1,179 @@
+use ratatui::prelude::*;
+use regex::{Regex, RegexBuilder};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchMode {
+    #[default]
+    Plain,
+    IgnoreCase,
+    Regex,
+}
+
+impl SearchMode {
+    pub fn next(self) -> Self {
+        match self {
+            SearchMode::Plain => SearchMode::IgnoreCase,
+            SearchMode::IgnoreCase => SearchMode::Regex,
+            SearchMode::Regex => SearchMode::Plain,
+        }
+    }
+}
+
+impl std::fmt::Display for SearchMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchMode::Plain => write!(f, "plain"),
+            SearchMode::IgnoreCase => write!(f, "ignore case"),
+            SearchMode::Regex => write!(f, "regex"),
+        }
+    }
+}
+
+/// Text searched in the rendered rows of the Log Stream.
+#[derive(Clone, Debug)]
+pub struct SearchQuery {
+    pub pattern: String,
+    pub mode: SearchMode,
+    regex: Regex,
+}
+
+impl SearchQuery {
+    pub fn new(pattern: &str, mode: SearchMode) -> Result<Self, String> {
+        // どのモードも正規表現に変換して同じ方法で検索する
+        let regex = match mode {
+            SearchMode::Plain => RegexBuilder::new(&regex::escape(pattern)).build(),
+            SearchMode::IgnoreCase => RegexBuilder::new(&regex::escape(pattern))
+                .case_insensitive(true)
+                .build(),
+            SearchMode::Regex => RegexBuilder::new(pattern).build(),
+        }
+        .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            pattern: pattern.to_string(),
+            mode,
+            regex,
+        })
+    }
+
+    pub fn is_match(&self, line: &Line) -> bool {
+        self.regex.is_match(&line_text(line))
+    }
+
+    /// Split the spans of `line` so that every match is drawn with `style` on top.
+    pub fn highlight(&self, line: Line<'static>, style: Style) -> Line<'static> {
+        let text = line_text(&line);
+        let ranges: Vec<(usize, usize)> = self
+            .regex
+            .find_iter(&text)
+            .filter(|m| !m.is_empty())
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        if ranges.is_empty() {
+            return line;
+        }
+
+        let mut spans = Vec::new();
+        let mut offset = 0;
+        for span in line.spans {
+            let content = span.content.as_ref();
+            let end = offset + content.len();
+
+            // spanの中のマッチ境界で分割する
+            let mut cuts = vec![0, content.len()];
+            for &(start, stop) in &ranges {
+                for cut in [start, stop] {
+                    if offset < cut && cut < end {
+                        cuts.push(cut - offset);
+                    }
+                }
+            }
+            cuts.sort_unstable();
+            cuts.dedup();
+
+            for window in cuts.windows(2) {
+                let (from, to) = (window[0], window[1]);
+                let is_matched = ranges
+                    .iter()
+                    .any(|&(start, stop)| start <= offset + from && offset + to <= stop);
+                let part_style = if is_matched {
+                    span.style.patch(style)
+                } else {
+                    span.style
+                };
+                spans.push(Span::styled(content[from..to].to_string(), part_style));
+            }
+            offset = end;
+        }
+        Line::from(spans).style(line.style)
+    }
+}
+
+fn line_text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_modes() {
+        let line = Line::from("Payment ERROR: card declined");
+
+        assert!(
+            SearchQuery::new("ERROR", SearchMode::Plain)
+                .unwrap()
+                .is_match(&line)
+        );
+        assert!(
+            !SearchQuery::new("error", SearchMode::Plain)
+                .unwrap()
+                .is_match(&line)
+        );
+        assert!(
+            SearchQuery::new("error", SearchMode::IgnoreCase)
+                .unwrap()
+                .is_match(&line)
+        );
+        assert!(
+            SearchQuery::new(r"card\s+decl", SearchMode::Regex)
+                .unwrap()
+                .is_match(&line)
+        );
+        // プレーンテキストでは正規表現の記号をそのまま探す
+        assert!(
+            !SearchQuery::new("card.", SearchMode::Plain)
+                .unwrap()
+                .is_match(&line)
+        );
+        assert!(SearchQuery::new("(", SearchMode::Regex).is_err());
+    }
+
+    #[test]
+    fn test_highlight_across_spans() {
+        let line = Line::from(vec![
+            Span::raw("{\"message\":"),
+            Span::styled("\"hello\"", Style::new().fg(Color::Red)),
+            Span::raw("}"),
+        ]);
+        let query = SearchQuery::new("message\":\"he", SearchMode::Plain).unwrap();
+        let highlighted = query.highlight(line, Style::new().bg(Color::Yellow));
+
+        let contents: Vec<_> = highlighted
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(contents, vec!["{\"", "message\":", "\"he", "llo\"", "}"]);
+        assert_eq!(highlighted.spans[1].style.bg, Some(Color::Yellow));
+        assert_eq!(
+            highlighted.spans[2].style,
+            Style::new().fg(Color::Red).bg(Color::Yellow)
+        );
+        assert_eq!(highlighted.spans[3].style, Style::new().fg(Color::Red));
+    }
+}
@@ -1,35 +1,114 @@
-use std::collections::VecDeque;
-use std::fs::File;
-use std::io::Write;
+use std::collections::{HashSet, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 
 use chrono::Utc;
-use chrono_tz::Asia::Tokyo;
 use color_eyre::Result;
 use ratatui::{prelude::*, widgets::*};
 use serde_json::Value;
 use tokio::sync::mpsc::UnboundedSender;
+use ulid::Ulid;
 
 use super::{
     Component,
     outer_layout::{self, Message},
+    text_input::{InputOutcome, TextInput},
 };
 use crate::action::ComponentAction;
-use crate::notification::show_notification;
-use crate::{action::Action, config::Config, date::get_diff};
-use arboard::Clipboard;
+use crate::{
+    action::Action,
+    app::Mode,
+    components::{
+        display_filter::DisplayFilter,
+        log_group_list::{LogGroup, SelectLogGroup},
+        log_level::LogLevel,
+        log_search::{SearchMode, SearchQuery},
+        profile_picker::SelectProfile,
+    },
+    config::{ColumnConfig, Config},
+    date::{TimeRange, get_diff},
+    history::{ReceiveEventsPage, SearchEvents},
+    live_tail::{ChangeLiveTailStatus, LiveTailStatus},
+    scrollback::Scrollback,
+};
+use crate::{
+    clipboard::copy_to_clipboard,
+    export::{
+        ExportFormat, Exporter, FilenameContext, complete_path, expand_home, render_filename,
+    },
+    notification::show_notification,
+};
+
+/// Number of logs read back from the disk at once.
+const SCROLLBACK_PAGE: usize = 200;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeFilterPattern {
+    pub filter_pattern: Option<String>,
+}
+
+impl ComponentAction for ChangeFilterPattern {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "ChangeFilterPattern"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+}
+
+/// `None` switches back to live tail.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeTimeRange {
+    pub time_range: Option<TimeRange>,
+}
+
+impl ComponentAction for ChangeTimeRange {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "ChangeTimeRange"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+}
 
 #[derive(Clone, Debug, PartialEq)]
-struct ExportLogs {
-    filepath: String,
+pub struct FetchNextPage;
+
+impl ComponentAction for FetchNextPage {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "FetchNextPage"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
 }
 
-impl ComponentAction for ExportLogs {
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconnectLiveTail;
+
+impl ComponentAction for ReconnectLiveTail {
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
 
     fn name(&self) -> &'static str {
-        "ExportLogs"
+        "ReconnectLiveTail"
     }
 
     fn clone_box(&self) -> Box<dyn ComponentAction> {
@@ -56,77 +135,1090 @@ impl ComponentAction for SelectLog {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Default)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Prompt {
+    FilterPattern,
+    TimeRange,
+    Search,
+    DisplayFilter,
+    ExportTimeRange,
+    ExportPath(ExportFormat),
+}
+
+/// Which logs `e` exports.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ExportScope {
+    /// Logs toggled with Enter
+    Saved,
+    /// Logs shown with the current display filter and levels
+    Visible,
+    /// Every received log, including the ones on disk and the ones queued while paused
+    Buffered,
+    /// Buffered logs in the range
+    TimeRange(TimeRange),
+}
+
+impl std::fmt::Display for ExportScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportScope::Saved => write!(f, "saved"),
+            ExportScope::Visible => write!(f, "visible"),
+            ExportScope::Buffered => write!(f, "buffered"),
+            ExportScope::TimeRange(time_range) => write!(f, "{time_range}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ExportMenu {
+    Scope(ListState),
+    Format(ListState),
+}
+
+/// A received log with what the table needs, worked out once when it arrives.
+#[derive(Clone, Debug)]
+struct ReceivedLog {
+    message: Message,
+    /// The content parsed as JSON, if it is
+    json: Option<Value>,
+    level: Option<LogLevel>,
+    /// The content as drawn in the Log column
+    line: Line<'static>,
+}
+
+impl ReceivedLog {
+    fn new(message: Message, message_only: bool) -> Self {
+        let json = serde_json::from_str(&message.content).ok();
+        let level = LogLevel::detect(&message.content, json.as_ref());
+        let line = content_line(&message.content, json.as_ref(), message_only);
+        Self {
+            message,
+            json,
+            level,
+            line,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct LogStream {
-    /// max: 1000
-    received_logs: VecDeque<Message>,
+    config: Config,
+
+    /// max: `scrollback` in the config
+    received_logs: VecDeque<ReceivedLog>,
+    /// Indices of `received_logs` shown in the table, in order. Updated when the logs or the
+    /// display filter and hidden levels change, so that drawing and moving don't filter again.
+    visible: Vec<usize>,
+    /// Logs evicted from `received_logs`
+    scrollback: Scrollback,
+    /// Number of the first log of `received_logs` in the order of arrival.
+    /// Logs numbered below `scrollback.len()` are also on disk.
+    first_number: usize,
 
     table_state: TableState,
 
     saved_logs: Vec<Message>,
+    /// Start of the rows selected with `v` or Shift+arrows
+    range_anchor: Option<Ulid>,
+
+    /// Open while choosing the logs to export and the format
+    export_menu: Option<ExportMenu>,
+    /// Logs being exported, collected when the scope is chosen
+    export_logs: Option<(ExportScope, Vec<Message>)>,
+    /// Format and path waiting for the confirmation to overwrite the existing file
+    overwrite_confirmation: Option<(ExportFormat, PathBuf)>,
+
+    /// Filter pattern of the current live tail session or search
+    filter_pattern: Option<String>,
+    /// Historical search instead of live tail while set
+    time_range: Option<TimeRange>,
+    prompt: Option<(Prompt, TextInput)>,
+
+    live_tail_status: LiveTailStatus,
+    searching: bool,
+    has_more_events: bool,
+
+    /// Incremental search in the received logs
+    search: Option<SearchQuery>,
+    search_mode: SearchMode,
+    search_error: Option<String>,
+    /// Search and selection to restore when the search prompt is cancelled
+    search_origin: Option<(Option<SearchQuery>, Option<usize>)>,
+
+    /// Hides the received logs that don't match without restarting the live tail
+    display_filter: Option<DisplayFilter>,
+    /// Logs of these levels are hidden. Logs without a detected level are always shown.
+    hidden_levels: HashSet<LogLevel>,
+
+    /// Extra columns for the selected log groups
+    columns: Vec<ColumnConfig>,
+    /// Show only the `message` field of JSON logs
+    message_only: bool,
+
+    /// Freezes the table. New logs are queued in `pending_logs` until resumed.
+    paused: bool,
+    /// max: `scrollback` in the config. Older ones are spilled to `scrollback`
+    pending_logs: Vec<Message>,
+    /// Queued logs written to `scrollback` after the received logs
+    spilled_logs: usize,
+    /// A search started while paused. Its results replace the received logs when resumed.
+    clear_on_resume: bool,
 }
 
 impl LogStream {
+    /// Forget the received logs, including those on the disk and those queued while paused.
+    fn clear_logs(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        self.received_logs.clear();
+        self.visible.clear();
+        self.pending_logs.clear();
+        self.spilled_logs = 0;
+        self.clear_on_resume = false;
+        self.first_number = 0;
+        self.range_anchor = None;
+        self.table_state = TableState::default();
+        if let Err(e) = self.scrollback.clear() {
+            tx.send(Action::Error(format!("Failed to clear scrollback: {e:#}")))?;
+        }
+        Ok(())
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.prompt.is_some() || self.export_menu.is_some() || self.overwrite_confirmation.is_some()
+    }
+
+    fn open_prompt(&mut self, prompt: Prompt, tx: &UnboundedSender<Action>) -> Result<()> {
+        let value = match prompt {
+            Prompt::FilterPattern => self.filter_pattern.clone().unwrap_or_default(),
+            Prompt::TimeRange => self
+                .time_range
+                .as_ref()
+                .map(|time_range| time_range.to_string())
+                .unwrap_or_default(),
+            Prompt::Search => {
+                self.search_origin = Some((self.search.clone(), self.table_state.selected()));
+                self.search_error = None;
+                String::new()
+            }
+            Prompt::DisplayFilter => self
+                .display_filter
+                .as_ref()
+                .map(|display_filter| display_filter.expression.clone())
+                .unwrap_or_default(),
+            Prompt::ExportTimeRange => String::new(),
+            Prompt::ExportPath(format) => self.default_export_path(format).display().to_string(),
+        };
+        self.prompt = Some((prompt, TextInput::new(&value)));
+        tx.send(Action::ChangeMode(Mode::Input))?;
+        Ok(())
+    }
+
+    fn handle_prompt(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        let Some((prompt, input)) = &mut self.prompt else {
+            return Ok(());
+        };
+        let prompt = *prompt;
+        if prompt == Prompt::Search && key.code == crossterm::event::KeyCode::Tab {
+            self.search_mode = self.search_mode.next();
+            let value = input.value().to_string();
+            return self.update_search(&value, &tx);
+        }
+        if let Prompt::ExportPath(_) = prompt
+            && key.code == crossterm::event::KeyCode::Tab
+        {
+            if let Some(completed) = complete_path(input.value()) {
+                *input = TextInput::new(&completed);
+            }
+            return Ok(());
+        }
+        match input.handle_key_event(key) {
+            InputOutcome::Pending => {
+                if prompt == Prompt::Search {
+                    let value = input.value().to_string();
+                    self.update_search(&value, &tx)?;
+                }
+            }
+            InputOutcome::Submit(value) => {
+                let value = value.trim();
+                match prompt {
+                    Prompt::FilterPattern => {
+                        self.filter_pattern = (!value.is_empty()).then(|| value.to_string());
+                        self.prompt = None;
+                        tx.send(Action::ChangeMode(Mode::Home))?;
+                        tx.send(Action::ComponentAction(Box::new(ChangeFilterPattern {
+                            filter_pattern: self.filter_pattern.clone(),
+                        })))?;
+                    }
+                    Prompt::TimeRange => {
+                        let time_range = if value.is_empty() {
+                            None
+                        } else {
+                            match TimeRange::parse(value, self.config.config.timezone) {
+                                Ok(time_range) => Some(time_range),
+                                Err(e) => {
+                                    // 入力を修正できるようにプロンプトは開いたままにする
+                                    tx.send(Action::Error(e))?;
+                                    return Ok(());
+                                }
+                            }
+                        };
+                        self.time_range = time_range.clone();
+                        self.has_more_events = false;
+                        self.prompt = None;
+                        tx.send(Action::ChangeMode(Mode::Home))?;
+                        tx.send(Action::ComponentAction(Box::new(ChangeTimeRange {
+                            time_range,
+                        })))?;
+                    }
+                    Prompt::DisplayFilter => {
+                        let display_filter = if value.is_empty() {
+                            None
+                        } else {
+                            match DisplayFilter::parse(value) {
+                                Ok(display_filter) => Some(display_filter),
+                                Err(e) => {
+                                    tx.send(Action::Error(e))?;
+                                    return Ok(());
+                                }
+                            }
+                        };
+                        self.change_visibility(|log_stream| {
+                            log_stream.display_filter = display_filter;
+                        });
+                        self.prompt = None;
+                        tx.send(Action::ChangeMode(Mode::Home))?;
+                    }
+                    Prompt::Search => {
+                        self.search_origin = None;
+                        self.search_error = None;
+                        self.prompt = None;
+                        tx.send(Action::ChangeMode(Mode::Home))?;
+                    }
+                    Prompt::ExportTimeRange => {
+                        match TimeRange::parse(value, self.config.config.timezone) {
+                            Ok(time_range) => {
+                                self.prompt = None;
+                                tx.send(Action::ChangeMode(Mode::Home))?;
+                                self.choose_export_scope(ExportScope::TimeRange(time_range), &tx)?;
+                            }
+                            Err(e) => tx.send(Action::Error(e))?,
+                        }
+                    }
+                    Prompt::ExportPath(format) => {
+                        let value = value.to_string();
+                        self.submit_export_path(format, &value, &tx)?;
+                    }
+                }
+            }
+            InputOutcome::Cancel => {
+                if prompt == Prompt::Search
+                    && let Some((search, selected)) = self.search_origin.take()
+                {
+                    self.search = search;
+                    self.search_error = None;
+                    self.table_state.select(selected);
+                }
+                if let Prompt::ExportPath(_) = prompt {
+                    self.export_logs = None;
+                }
+                self.prompt = None;
+                tx.send(Action::ChangeMode(Mode::Home))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Search as the pattern is typed, starting from the selection before the prompt was opened.
+    fn update_search(&mut self, pattern: &str, tx: &UnboundedSender<Action>) -> Result<()> {
+        let origin = self
+            .search_origin
+            .as_ref()
+            .and_then(|(_, selected)| *selected);
+        if pattern.is_empty() {
+            self.search = None;
+            self.search_error = None;
+            self.table_state.select(origin);
+            return Ok(());
+        }
+
+        match SearchQuery::new(pattern, self.search_mode) {
+            Ok(search) => {
+                self.search = Some(search);
+                self.search_error = None;
+                if !self.jump_to_match(origin, true, true, tx)? {
+                    self.table_state.select(origin);
+                }
+            }
+            Err(e) => {
+                self.search = None;
+                self.search_error = Some(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// The received logs shown in the table. Row indices of the table refer to this view.
+    fn visible_logs(&self) -> Vec<&ReceivedLog> {
+        self.visible
+            .iter()
+            .map(|&index| &self.received_logs[index])
+            .collect()
+    }
+
+    /// Filter all the received logs again after the display filter or hidden levels changed.
+    fn refresh_visible(&mut self) {
+        self.visible = (0..self.received_logs.len())
+            .filter(|&index| self.is_visible(&self.received_logs[index]))
+            .collect();
+    }
+
+    /// Change which logs are shown keeping the selected log, or following if it was.
+    fn change_visibility(&mut self, change: impl FnOnce(&mut Self)) {
+        let is_follow_log = self.is_follow_log();
+        let selected_id = self.get_selected_log().map(|message| message.id);
+
+        change(self);
+        self.refresh_visible();
+
+        let visible_logs = self.visible_logs();
+        let selected = if is_follow_log {
+            Some(visible_logs.len())
+        } else {
+            selected_id.and_then(|id| visible_logs.iter().position(|log| log.message.id == id))
+        };
+        self.table_state.select(selected);
+    }
+
+    /// Indices of the matching rows in `visible_logs`.
+    fn search_matches(&self, visible_logs: &[&ReceivedLog]) -> Vec<usize> {
+        let Some(search) = &self.search else {
+            return Vec::new();
+        };
+        visible_logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| search.is_match(&log.line))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Select the next (or previous) matching row from `from`, wrapping around the buffer.
+    fn jump_to_match(
+        &mut self,
+        from: Option<usize>,
+        forward: bool,
+        inclusive: bool,
+        tx: &UnboundedSender<Action>,
+    ) -> Result<bool> {
+        let matches = self.search_matches(&self.visible_logs());
+        let target = match (from, forward) {
+            (None, true) => matches.first(),
+            (None, false) => matches.last(),
+            (Some(from), true) => matches
+                .iter()
+                .find(|&&index| index > from || (inclusive && index == from))
+                .or(matches.first()),
+            (Some(from), false) => matches
+                .iter()
+                .rev()
+                .find(|&&index| index < from || (inclusive && index == from))
+                .or(matches.last()),
+        };
+        let Some(&target) = target else {
+            return Ok(false);
+        };
+
+        self.table_state.select(Some(target));
+        if let Some(selected_log) = self.get_selected_log() {
+            tx.send(Action::ComponentAction(Box::new(SelectLog {
+                selected_log: selected_log.clone(),
+            })))?;
+        }
+        Ok(true)
+    }
+
+    fn push_logs(&mut self, messages: &[Message]) -> Result<()> {
+        let is_follow_log = self.is_follow_log();
+
+        // レベルの判定とJSONのパースは受信時に一度だけ行う
+        let message_only = self.message_only;
+        for message in messages {
+            let log = ReceivedLog::new(message.clone(), message_only);
+            if self.is_visible(&log) {
+                self.visible.push(self.received_logs.len());
+            }
+            self.received_logs.push_back(log);
+        }
+        let excess = self
+            .received_logs
+            .len()
+            .saturating_sub(self.config.config.scrollback);
+        let evicted: Vec<ReceivedLog> = self.received_logs.drain(0..excess).collect();
+        // 追い出したログの分だけ表示中の行の位置を詰める
+        let evicted_rows = self.visible.partition_point(|&index| index < excess);
+        self.visible.drain(0..evicted_rows);
+        for index in &mut self.visible {
+            *index -= excess;
+        }
+
+        if is_follow_log {
+            // 表示フィルターで隠れた行を除いたFollow行を選択する
+            self.table_state.select(Some(self.visible.len()));
+        } else if let Some(selected) = self.table_state.selected() {
+            // 同じログを選択したままにする
+            self.table_state
+                .select(Some(selected.saturating_sub(evicted_rows)));
+        }
+
+        // 読み戻したログは既にディスクにあるので書き込まない
+        let stored = self.scrollback.len().saturating_sub(self.first_number);
+        self.first_number += evicted.len();
+        let evicted: Vec<Message> = evicted.into_iter().map(|log| log.message).collect();
+        self.scrollback
+            .append(evicted.get(stored..).unwrap_or_default())
+    }
+
+    /// Queue logs received while paused, spilling the oldest ones to the disk beyond `scrollback`.
+    fn queue_logs(&mut self, messages: &[Message]) -> Result<()> {
+        self.pending_logs.extend_from_slice(messages);
+        let excess = self
+            .pending_logs
+            .len()
+            .saturating_sub(self.config.config.scrollback);
+        if excess == 0 {
+            return Ok(());
+        }
+        let overflow: Vec<Message> = self.pending_logs.drain(0..excess).collect();
+        if self.clear_on_resume {
+            // 表示中のログは置き換えられるので、その後ろには書かずに古いものを捨てる
+            return Ok(());
+        }
+
+        if self.spilled_logs == 0 {
+            // 番号が続くように、表示中のログでまだディスクに無いものを先に書く
+            let stored = self.scrollback.len().saturating_sub(self.first_number);
+            let unstored: Vec<Message> = self
+                .received_logs
+                .iter()
+                .skip(stored)
+                .map(|log| log.message.clone())
+                .collect();
+            self.scrollback.append(&unstored)?;
+        }
+        self.spilled_logs += overflow.len();
+        self.scrollback.append(&overflow)
+    }
+
+    fn toggle_pause(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        if !self.paused {
+            self.paused = true;
+            return Ok(());
+        }
+
+        self.paused = false;
+        let pending_logs = std::mem::take(&mut self.pending_logs);
+        if self.clear_on_resume {
+            self.clear_logs(tx)?;
+        } else if self.spilled_logs > 0 {
+            // 表示中のログと溜まったログの古いものはディスクにあるので、その続きから表示する
+            self.first_number += self.received_logs.len() + self.spilled_logs;
+            self.received_logs.clear();
+            self.visible.clear();
+            self.spilled_logs = 0;
+            self.range_anchor = None;
+        }
+        let result = self.push_logs(&pending_logs);
+        self.table_state.select(Some(self.visible_logs().len()));
+        result
+    }
+
+    /// Show the logs, or queue them while paused.
+    fn receive_logs(&mut self, messages: &[Message], tx: &UnboundedSender<Action>) -> Result<()> {
+        let result = if self.paused {
+            self.queue_logs(messages)
+        } else {
+            self.push_logs(messages)
+        };
+        if let Err(e) = result {
+            tx.send(Action::Error(format!("Failed to write scrollback: {e:#}")))?;
+        }
+        Ok(())
+    }
+
+    /// Page older logs back in from the disk when scrolling up past the first row.
+    fn load_older_logs(&mut self) -> Result<()> {
+        let start = self.first_number.saturating_sub(SCROLLBACK_PAGE);
+        let logs: Vec<ReceivedLog> = self
+            .scrollback
+            .read(start..self.first_number)?
+            .into_iter()
+            .map(|message| ReceivedLog::new(message, self.message_only))
+            .collect();
+        let count = logs.len();
+        let loaded: Vec<usize> = (0..count)
+            .filter(|&index| self.is_visible(&logs[index]))
+            .collect();
+        for index in &mut self.visible {
+            *index += count;
+        }
+        self.visible.splice(0..0, loaded.iter().copied());
+        for log in logs.into_iter().rev() {
+            self.received_logs.push_front(log);
+        }
+        let loaded = loaded.len();
+        self.first_number = start;
+        if let Some(selected) = self.table_state.selected() {
+            self.table_state.select(Some(selected + loaded));
+        }
+        Ok(())
+    }
+
+    fn is_visible(&self, log: &ReceivedLog) -> bool {
+        if log
+            .level
+            .is_some_and(|level| self.hidden_levels.contains(&level))
+        {
+            return false;
+        }
+        self.display_filter
+            .as_ref()
+            .is_none_or(|display_filter| display_filter.matches(&log.message, log.json.as_ref()))
+    }
+
+    /// Use the global columns and the ones configured for the selected log groups.
+    fn apply_log_group_config(&mut self, log_groups: &[LogGroup]) {
+        let app_config = &self.config.config;
+        let log_group_configs: Vec<_> = app_config
+            .log_groups
+            .iter()
+            .filter(|log_group_config| {
+                log_groups
+                    .iter()
+                    .any(|log_group| log_group.name == log_group_config.log_group)
+            })
+            .collect();
+
+        let mut columns = app_config.columns.clone();
+        for column in log_group_configs
+            .iter()
+            .flat_map(|log_group_config| &log_group_config.columns)
+        {
+            // 同じパスの列は一つだけ表示する
+            if !columns.iter().any(|c| c.path == column.path) {
+                columns.push(column.clone());
+            }
+        }
+        self.columns = columns;
+        let message_only = log_group_configs
+            .iter()
+            .rev()
+            .find_map(|log_group_config| log_group_config.message_only)
+            .unwrap_or(app_config.message_only);
+        if message_only != self.message_only {
+            self.message_only = message_only;
+            for log in &mut self.received_logs {
+                log.line = content_line(&log.message.content, log.json.as_ref(), message_only);
+            }
+        }
+    }
+
+    fn column_values(&self, log: &ReceivedLog) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|column| column.value(log.json.as_ref()))
+            .collect()
+    }
+
+    fn level_style(&self, level: LogLevel) -> Style {
+        self.config
+            .styles
+            .get(&Mode::Home)
+            .and_then(|styles| styles.get(level.style_key()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn title(
+        &self,
+        shown: usize,
+        matches: &[usize],
+        selected_range: Option<&std::ops::RangeInclusive<usize>>,
+    ) -> (String, Style) {
+        let mut title = match &self.filter_pattern {
+            Some(filter_pattern) => format!("Log Stream [filter: {}]", filter_pattern),
+            None => "Log Stream".to_string(),
+        };
+        if self.paused {
+            title.push_str(&format!(
+                " [paused: +{} new]",
+                self.spilled_logs + self.pending_logs.len()
+            ));
+        }
+        if self.first_number > 0 {
+            title.push_str(&format!(" [{} older on disk]", self.first_number));
+        }
+        let mut filters = Vec::new();
+        if let Some(display_filter) = &self.display_filter {
+            filters.push(display_filter.expression.clone());
+        }
+        if !self.hidden_levels.is_empty() {
+            let hidden_levels: Vec<&str> = LogLevel::ALL
+                .iter()
+                .filter(|level| self.hidden_levels.contains(level))
+                .map(|level| level.label())
+                .collect();
+            filters.push(format!("hide {}", hidden_levels.join("/")));
+        }
+        if !filters.is_empty() {
+            title.push_str(&format!(
+                " [display: {}, {} of {} shown]",
+                filters.join(", "),
+                shown,
+                self.received_logs.len()
+            ));
+        }
+        if let Some(search) = &self.search {
+            let current = self
+                .table_state
+                .selected()
+                .and_then(|selected| matches.iter().position(|&index| index == selected))
+                .map_or("-".to_string(), |position| (position + 1).to_string());
+            title.push_str(&format!(
+                " [/{} ({}) {}/{}]",
+                search.pattern,
+                search.mode,
+                current,
+                matches.len()
+            ));
+        }
+
+        if let Some(range) = selected_range {
+            title.push_str(&format!(
+                " [visual: {} rows, Enter: save, Esc: cancel]",
+                range.clone().count()
+            ));
+        }
+
+        if let Some(time_range) = &self.time_range {
+            title.push_str(&format!(" [range: {}]", time_range));
+            if self.searching {
+                title.push_str(" (searching...)");
+            } else if self.has_more_events {
+                title.push_str(" (m: more)");
+            } else {
+                title.push_str(" (complete)");
+            }
+            return (title, Style::new().fg(Color::Cyan));
+        }
+
+        if self.live_tail_status != LiveTailStatus::Idle {
+            title.push_str(&format!(" ({})", self.live_tail_status));
+        }
+        let title_style = match self.live_tail_status {
+            LiveTailStatus::Reconnecting { .. } => Style::new().fg(Color::Yellow),
+            LiveTailStatus::Disconnected(_) => Style::new().fg(Color::Red),
+            _ => Style::new(),
+        };
+        (title, title_style)
+    }
+
     fn is_follow_log(&self) -> bool {
         // 先頭を選択している場合のみtrue
-        self.table_state.selected() == Some(self.received_logs.len())
+        // 表示フィルターがある場合は表示中の行の末尾（Follow行）で判定する
+        self.table_state.selected() == Some(self.visible.len())
     }
 
     fn get_selected_log(&self) -> Option<&Message> {
-        if let Some(index) = self.table_state.selected() {
-            if let Some(message) = self.received_logs.get(index) {
-                return Some(message);
+        self.table_state
+            .selected()
+            .and_then(|index| self.visible.get(index))
+            .map(|&index| &self.received_logs[index].message)
+    }
+
+    /// Path suggested in the export prompt, from `export_dir` and `export_filename`.
+    fn default_export_path(&self, format: ExportFormat) -> PathBuf {
+        let app_config = &self.config.config;
+        let export_dir = app_config
+            .export_dir
+            .clone()
+            .unwrap_or_else(|| app_config.data_dir.join("exports"));
+        export_dir.join(render_filename(
+            &app_config.export_filename,
+            &FilenameContext {
+                datetime: Utc::now().with_timezone(&app_config.timezone),
+                messages: self.export_logs.as_ref().map_or(&[], |(_, logs)| logs),
+                format,
+            },
+        ))
+    }
+
+    /// Every received log in the order of arrival.
+    fn buffered_logs(&self) -> Result<Vec<Message>> {
+        // 画面に読み戻したログはディスクにもあるので、ディスクからはそれより古いものだけ読む
+        let mut logs = self
+            .scrollback
+            .read(0..self.first_number.min(self.scrollback.len()))?;
+        logs.extend(self.received_logs.iter().map(|log| log.message.clone()));
+        let spilled = self.first_number + self.received_logs.len();
+        logs.extend(self.scrollback.read(spilled..spilled + self.spilled_logs)?);
+        logs.extend(self.pending_logs.iter().cloned());
+        Ok(logs)
+    }
+
+    fn collect_export_logs(&self, scope: &ExportScope) -> Result<Vec<Message>> {
+        Ok(match scope {
+            ExportScope::Saved => self.saved_logs.clone(),
+            ExportScope::Visible => self
+                .visible_logs()
+                .into_iter()
+                .map(|log| log.message.clone())
+                .collect(),
+            ExportScope::Buffered => self.buffered_logs()?,
+            ExportScope::TimeRange(time_range) => {
+                let (start, end) = time_range.resolve(Utc::now());
+                let mut logs = self.buffered_logs()?;
+                logs.retain(|log| {
+                    log.datetime >= start && end.is_none_or(|end| log.datetime < end)
+                });
+                logs
+            }
+        })
+    }
+
+    /// Collect the logs of the scope and move on to the format menu.
+    fn choose_export_scope(
+        &mut self,
+        scope: ExportScope,
+        tx: &UnboundedSender<Action>,
+    ) -> Result<()> {
+        self.export_menu = None;
+        let logs = match self.collect_export_logs(&scope) {
+            Ok(logs) => logs,
+            Err(e) => {
+                tx.send(Action::Error(format!("Failed to read scrollback: {e:#}")))?;
+                return Ok(());
             }
+        };
+        if logs.is_empty() {
+            show_notification(
+                self.config.config.notification,
+                tx,
+                "Log Export",
+                &format!("No {scope} logs to export."),
+            );
+            return Ok(());
         }
-        None
+        self.export_logs = Some((scope, logs));
+        self.export_menu = Some(ExportMenu::Format(
+            ListState::default().with_selected(Some(0)),
+        ));
+        Ok(())
     }
 
-    fn export_saved_logs(&mut self) -> Result<String> {
-        if self.saved_logs.is_empty() {
-            return Ok(String::new());
+    /// Write the logs being exported and return the absolute path of the file.
+    fn export_logs_to(&self, format: ExportFormat, path: &Path) -> Result<PathBuf> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
         }
+        let mut file = BufWriter::new(File::create(path)?);
+        let logs = self.export_logs.as_ref().map_or(&[][..], |(_, logs)| logs);
+        self.write_logs(&mut file, format, logs)?;
+        file.flush()?;
 
-        let now = Utc::now();
-        let filename = format!(
-            "saved_logs_{}.jsonl",
-            now.with_timezone(&Tokyo).format("%Y%m%d_%H%M%S")
-        );
-        let mut file = File::create(&filename)?;
-        for message in &self.saved_logs {
-            writeln!(file, "{}", message.content)?;
+        Ok(std::path::absolute(path)?)
+    }
+
+    fn write_logs(
+        &self,
+        writer: &mut impl Write,
+        format: ExportFormat,
+        logs: &[Message],
+    ) -> Result<()> {
+        Exporter {
+            format,
+            columns: &self.columns,
+            timezone: self.config.config.timezone,
         }
+        .write(writer, logs)
+    }
 
-        Ok(filename)
+    /// Rows between the anchor and the cursor, as indices of `visible_logs`.
+    fn selected_range(
+        &self,
+        visible_logs: &[&ReceivedLog],
+    ) -> Option<std::ops::RangeInclusive<usize>> {
+        let anchor = self.range_anchor?;
+        let anchor = visible_logs
+            .iter()
+            .position(|log| log.message.id == anchor)?;
+        // Followの行は最後のログとして扱う
+        let cursor = self
+            .table_state
+            .selected()?
+            .min(visible_logs.len().checked_sub(1)?);
+        Some(anchor.min(cursor)..=anchor.max(cursor))
+    }
+
+    /// Save the rows in the range, or unsave them if all of them are saved.
+    fn toggle_saved_range(&mut self, range: std::ops::RangeInclusive<usize>) {
+        let logs: Vec<Message> = self.visible_logs()[range]
+            .iter()
+            .map(|log| log.message.clone())
+            .collect();
+        if logs.iter().all(|log| self.saved_logs.contains(log)) {
+            self.saved_logs.retain(|saved| !logs.contains(saved));
+        } else {
+            for log in logs {
+                if !self.saved_logs.contains(&log) {
+                    self.saved_logs.push(log);
+                }
+            }
+        }
+    }
+
+    fn export(
+        &mut self,
+        format: ExportFormat,
+        path: &Path,
+        tx: &UnboundedSender<Action>,
+    ) -> Result<()> {
+        self.prompt = None;
+        self.overwrite_confirmation = None;
+        tx.send(Action::ChangeMode(Mode::Home))?;
+        let result = self.export_logs_to(format, path);
+        let count = self.export_logs.take().map_or(0, |(_, logs)| logs.len());
+        match result {
+            Ok(path) => show_notification(
+                self.config.config.notification,
+                tx,
+                "Log Export",
+                &format!("Exported {} logs to {}", count, path.display()),
+            ),
+            Err(e) => tx.send(Action::Error(format!(
+                "Failed to export logs to {}: {e:#}",
+                path.display()
+            )))?,
+        }
+        Ok(())
+    }
+
+    /// Export to the path typed in the prompt, asking first if the file exists.
+    fn submit_export_path(
+        &mut self,
+        format: ExportFormat,
+        value: &str,
+        tx: &UnboundedSender<Action>,
+    ) -> Result<()> {
+        if value.is_empty() {
+            tx.send(Action::Error("Export path is empty".to_string()))?;
+            return Ok(());
+        }
+        let path = expand_home(value);
+        if path.is_dir() {
+            tx.send(Action::Error(format!("{} is a directory", path.display())))?;
+            return Ok(());
+        }
+        if path.exists() {
+            self.prompt = None;
+            self.overwrite_confirmation = Some((format, path));
+            return Ok(());
+        }
+        self.export(format, &path, tx)
+    }
+
+    fn handle_overwrite_confirmation(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        let Some((format, path)) = self.overwrite_confirmation.clone() else {
+            return Ok(());
+        };
+        match key.code {
+            crossterm::event::KeyCode::Char('y') | crossterm::event::KeyCode::Enter => {
+                self.export(format, &path, &tx)?;
+            }
+            crossterm::event::KeyCode::Char('n') | crossterm::event::KeyCode::Esc => {
+                // パスを変更できるようにプロンプトに戻る
+                self.overwrite_confirmation = None;
+                self.prompt = Some((
+                    Prompt::ExportPath(format),
+                    TextInput::new(&path.display().to_string()),
+                ));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_export_menu(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        let (Some(ExportMenu::Scope(list_state)) | Some(ExportMenu::Format(list_state))) =
+            &mut self.export_menu
+        else {
+            return Ok(());
+        };
+        let chosen = match key.code {
+            crossterm::event::KeyCode::Up => {
+                list_state.select_previous();
+                None
+            }
+            crossterm::event::KeyCode::Down => {
+                list_state.select_next();
+                None
+            }
+            crossterm::event::KeyCode::Enter => list_state.selected(),
+            crossterm::event::KeyCode::Char(c @ '1'..='9') => Some(c as usize - '1' as usize),
+            crossterm::event::KeyCode::Esc | crossterm::event::KeyCode::Char('e') => {
+                self.export_menu = None;
+                self.export_logs = None;
+                None
+            }
+            _ => None,
+        };
+        let Some(index) = chosen else {
+            return Ok(());
+        };
+
+        match self.export_menu {
+            Some(ExportMenu::Scope(_)) => match index {
+                0 => self.choose_export_scope(ExportScope::Saved, &tx)?,
+                1 => self.choose_export_scope(ExportScope::Visible, &tx)?,
+                2 => self.choose_export_scope(ExportScope::Buffered, &tx)?,
+                3 => {
+                    self.export_menu = None;
+                    self.open_prompt(Prompt::ExportTimeRange, &tx)?;
+                }
+                _ => {}
+            },
+            Some(ExportMenu::Format(_)) => {
+                if let Some(&format) = ExportFormat::ALL.get(index) {
+                    self.export_menu = None;
+                    self.open_prompt(Prompt::ExportPath(format), &tx)?;
+                }
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    fn draw_export_menu(&mut self, frame: &mut Frame, area: Rect, shown: usize) {
+        let (title, labels) = match &self.export_menu {
+            Some(ExportMenu::Scope(_)) => (
+                "Export (Enter: choose, Esc: close)".to_string(),
+                vec![
+                    format!("Saved logs ({})", self.saved_logs.len()),
+                    format!("Visible logs ({})", shown),
+                    format!(
+                        "All buffered logs ({})",
+                        self.first_number
+                            + self.received_logs.len()
+                            + self.spilled_logs
+                            + self.pending_logs.len()
+                    ),
+                    "Buffered logs in a time range".to_string(),
+                ],
+            ),
+            Some(ExportMenu::Format(_)) => (
+                match &self.export_logs {
+                    Some((scope, logs)) => {
+                        format!("Export {} {scope} logs as (Esc: close)", logs.len())
+                    }
+                    None => "Export as (Esc: close)".to_string(),
+                },
+                ExportFormat::ALL
+                    .iter()
+                    .map(|format| format!("{} (.{})", format.label(), format.extension()))
+                    .collect(),
+            ),
+            None => return,
+        };
+        let Some(ExportMenu::Scope(list_state) | ExportMenu::Format(list_state)) =
+            &mut self.export_menu
+        else {
+            return;
+        };
+
+        let items: Vec<ListItem> = labels
+            .iter()
+            .enumerate()
+            .map(|(index, label)| ListItem::new(format!("{} {}", index + 1, label)))
+            .collect();
+        let width = area.width.min((title.len() as u16 + 2).max(36));
+        let height = (items.len() as u16 + 2).min(area.height);
+        let menu_area = Rect::new(
+            area.x + (area.width - width) / 2,
+            area.y + (area.height - height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, menu_area);
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::new().reversed())
+                .block(Block::bordered().title(title)),
+            menu_area,
+            list_state,
+        );
     }
 }
 
 impl Component for LogStream {
-    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+    fn register_action_handler(&mut self, _tx: UnboundedSender<Action>) -> Result<()> {
         Ok(())
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        if !config.config.data_dir.as_os_str().is_empty() {
+            self.scrollback = Scrollback::new(&config.config.data_dir.join("scrollback"));
+        }
+        self.config = config;
+        self.apply_log_group_config(&[]);
         Ok(())
     }
 
     fn update(&mut self, action: Action, tx: UnboundedSender<Action>) -> Result<()> {
-        if let Action::ComponentAction(action) = action {
-            if let Some(action) = action
-                .as_any()
-                .downcast_ref::<outer_layout::ReceiveNewLog>()
-            {
-                let is_follow_log = self.is_follow_log();
+        let Action::ComponentAction(action) = action else {
+            return Ok(());
+        };
 
-                self.received_logs.extend(action.new_messages.clone());
-                if self.received_logs.len() > 1000 {
-                    self.received_logs
-                        .drain(0..(self.received_logs.len() - 1000));
-                }
-
-                if is_follow_log {
-                    self.table_state.select_last();
-                }
+        if let Some(action) = action.as_any().downcast_ref::<ChangeLiveTailStatus>() {
+            self.live_tail_status = action.status.clone();
+        } else if action.as_any().downcast_ref::<SelectProfile>().is_some() {
+            // 前のアカウントのログと条件を捨てる。保存したログは残す
+            self.clear_logs(&tx)?;
+            self.filter_pattern = None;
+            self.time_range = None;
+            self.has_more_events = false;
+            self.searching = false;
+            self.apply_log_group_config(&[]);
+        } else if let Some(action) = action.as_any().downcast_ref::<SelectLogGroup>() {
+            self.apply_log_group_config(&action.log_groups);
+        } else if let Some(action) = action
+            .as_any()
+            .downcast_ref::<outer_layout::ReceiveNewLog>()
+        {
+            self.receive_logs(&action.new_messages, &tx)?;
+        } else if let Some(action) = action.as_any().downcast_ref::<SearchEvents>() {
+            self.searching = true;
+            if action.first_page && self.paused {
+                // 表示は止めたまま、再開したときに新しい検索の結果に置き換える
+                self.clear_on_resume = true;
+                self.pending_logs.clear();
+                self.spilled_logs = 0;
+            } else if action.first_page {
+                self.clear_logs(&tx)?;
             }
+        } else if let Some(action) = action.as_any().downcast_ref::<ReceiveEventsPage>() {
+            self.searching = false;
+            self.has_more_events = !action.next_requests.is_empty();
+            self.receive_logs(&action.messages, &tx)?;
         }
         Ok(())
     }
@@ -136,21 +1228,62 @@ impl Component for LogStream {
         key: crossterm::event::KeyEvent,
         tx: UnboundedSender<Action>,
     ) -> Result<()> {
+        if self.export_menu.is_some() {
+            return self.handle_export_menu(key, tx);
+        }
+        if self.overwrite_confirmation.is_some() {
+            return self.handle_overwrite_confirmation(key, tx);
+        }
+        if self.is_editing() {
+            return self.handle_prompt(key, tx);
+        }
+
+        // Shift+矢印で範囲選択を始める
+        if key
+            .modifiers
+            .contains(crossterm::event::KeyModifiers::SHIFT)
+            && matches!(
+                key.code,
+                crossterm::event::KeyCode::Up | crossterm::event::KeyCode::Down
+            )
+            && self.range_anchor.is_none()
+        {
+            self.range_anchor = self.get_selected_log().map(|log| log.id);
+        }
+
         match key.code {
+            crossterm::event::KeyCode::Enter if self.range_anchor.is_some() => {
+                if let Some(range) = self.selected_range(&self.visible_logs()) {
+                    self.toggle_saved_range(range);
+                }
+                self.range_anchor = None;
+            }
+            crossterm::event::KeyCode::Char('v') => {
+                self.range_anchor = match self.range_anchor {
+                    Some(_) => None,
+                    None => self.get_selected_log().map(|log| log.id),
+                };
+            }
+            crossterm::event::KeyCode::Esc if self.range_anchor.is_some() => {
+                self.range_anchor = None;
+            }
             crossterm::event::KeyCode::Enter => {
-                if let Some(selected_index) = self.table_state.selected() {
-                    let selected_log = self.received_logs.get(selected_index);
-                    if let Some(log) = selected_log {
-                        if self.saved_logs.iter().any(|x| x.id == log.id) {
-                            self.saved_logs.retain(|x| x.id != log.id);
-                        } else {
-                            self.saved_logs.push(log.clone());
-                        }
+                if let Some(log) = self.get_selected_log().cloned() {
+                    if self.saved_logs.iter().any(|x| x.id == log.id) {
+                        self.saved_logs.retain(|x| x.id != log.id);
+                    } else {
+                        self.saved_logs.push(log);
                     }
                 }
             }
 
             crossterm::event::KeyCode::Up | crossterm::event::KeyCode::PageUp => {
+                if self.table_state.selected() == Some(0)
+                    && self.first_number > 0
+                    && let Err(e) = self.load_older_logs()
+                {
+                    tx.send(Action::Error(format!("Failed to read scrollback: {e:#}")))?;
+                }
                 self.table_state.scroll_up_by(1);
                 if let Some(selected_log) = self.get_selected_log() {
                     tx.send(Action::ComponentAction(Box::new(SelectLog {
@@ -169,22 +1302,78 @@ impl Component for LogStream {
 
             crossterm::event::KeyCode::Char('c') => {
                 if let Some(message) = self.get_selected_log() {
-                    let mut clipboard = Clipboard::new().unwrap();
-                    clipboard.set_text(message.url.clone()).unwrap();
-
-                    show_notification(
-                        "Copy URL",
-                        &format!("Copied URL to clipboard: {}", message.url),
-                    );
+                    // リージョンが分からない場合やローカルのログにはURLが無い
+                    if message.url.is_empty() {
+                        tx.send(Action::Error(
+                            "This log has no console URL: the AWS region is unknown or the log is not from CloudWatch"
+                                .to_string(),
+                        ))?;
+                        return Ok(());
+                    }
+                    match copy_to_clipboard(
+                        &message.url,
+                        self.config.config.clipboard_command.as_deref(),
+                    ) {
+                        Ok(()) => show_notification(
+                            self.config.config.notification,
+                            &tx,
+                            "Copy URL",
+                            &format!("Copied URL to clipboard: {}", message.url),
+                        ),
+                        Err(e) => {
+                            tx.send(Action::Error(format!("Failed to copy to clipboard: {e:#}")))?
+                        }
+                    }
                 }
             }
-            crossterm::event::KeyCode::Char('e') => {
-                if let Ok(path) = self.export_saved_logs() {
-                    show_notification("Log Export", &format!("Exported logs to {}", path));
-                } else {
-                    show_notification("Log Export", "Failed to export logs.");
+            crossterm::event::KeyCode::Char('f') => {
+                self.open_prompt(Prompt::FilterPattern, &tx)?;
+            }
+            crossterm::event::KeyCode::Char('F') => {
+                self.open_prompt(Prompt::DisplayFilter, &tx)?;
+            }
+            crossterm::event::KeyCode::Char(c @ '1'..='5') => {
+                let level = LogLevel::ALL[c as usize - '1' as usize];
+                self.change_visibility(|log_stream| {
+                    if !log_stream.hidden_levels.remove(&level) {
+                        log_stream.hidden_levels.insert(level);
+                    }
+                });
+            }
+            crossterm::event::KeyCode::Char('h') => {
+                self.open_prompt(Prompt::TimeRange, &tx)?;
+            }
+            // 止めている間は続きを取得しない
+            crossterm::event::KeyCode::Char('m')
+                if self.time_range.is_some()
+                    && self.has_more_events
+                    && !self.searching
+                    && !self.paused =>
+            {
+                tx.send(Action::ComponentAction(Box::new(FetchNextPage)))?;
+            }
+            crossterm::event::KeyCode::Char('/') => {
+                self.open_prompt(Prompt::Search, &tx)?;
+            }
+            crossterm::event::KeyCode::Char('n') if self.search.is_some() => {
+                self.jump_to_match(self.table_state.selected(), true, false, &tx)?;
+            }
+            crossterm::event::KeyCode::Char('N') if self.search.is_some() => {
+                self.jump_to_match(self.table_state.selected(), false, false, &tx)?;
+            }
+            crossterm::event::KeyCode::Char(' ') => {
+                if let Err(e) = self.toggle_pause(&tx) {
+                    tx.send(Action::Error(format!("Failed to write scrollback: {e:#}")))?;
                 }
             }
+            crossterm::event::KeyCode::Char('r') => {
+                tx.send(Action::ComponentAction(Box::new(ReconnectLiveTail)))?;
+            }
+            crossterm::event::KeyCode::Char('e') => {
+                self.export_menu = Some(ExportMenu::Scope(
+                    ListState::default().with_selected(Some(0)),
+                ));
+            }
             _ => {}
         }
 
@@ -192,59 +1381,182 @@ impl Component for LogStream {
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-        let rows = self
-            .received_logs
-            .iter()
-            .map(|message| {
-                let is_highlighted = self.saved_logs.contains(message);
-                let content_line = convert_to_line(&message.content);
-                Row::new(vec![Line::from(get_diff(message.datetime)), content_line]).style(
-                    if is_highlighted {
-                        Style::new().bg(Color::Yellow)
-                    } else {
-                        Style::new()
-                    },
-                )
+        let area = if let Some((prompt, input)) = &self.prompt {
+            let [table_area, input_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(area);
+            let title = match prompt {
+                Prompt::FilterPattern => "Filter pattern (Enter: apply, Esc: cancel)".to_string(),
+                Prompt::TimeRange => {
+                    "Time range, e.g. -15m or 2025-06-07 10:00..2025-06-07 11:00 (empty: live tail)"
+                        .to_string()
+                }
+                Prompt::DisplayFilter => {
+                    "Display filter, e.g. level=ERROR and service~cart (empty: show all)"
+                        .to_string()
+                }
+                Prompt::Search => match &self.search_error {
+                    Some(e) => format!("Search ({}): {}", self.search_mode, e),
+                    None => format!(
+                        "Search ({}, Tab: change mode, Enter: done, Esc: cancel)",
+                        self.search_mode
+                    ),
+                },
+                Prompt::ExportTimeRange => {
+                    "Export logs in a time range, e.g. -15m or 2025-06-07 10:00..2025-06-07 10:05"
+                        .to_string()
+                }
+                Prompt::ExportPath(format) => format!(
+                    "Export {} logs as {} to (Tab: complete, Enter: export, Esc: cancel)",
+                    self.export_logs.as_ref().map_or(0, |(_, logs)| logs.len()),
+                    format.label()
+                ),
+            };
+            input.draw(frame, input_area, &title);
+            table_area
+        } else if let Some((_, path)) = &self.overwrite_confirmation {
+            let [table_area, confirmation_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(area);
+            frame.render_widget(
+                Paragraph::new(format!("{} already exists", path.display()))
+                    .style(Style::new().fg(Color::Yellow))
+                    .block(
+                        Block::bordered().title("Overwrite? (y: overwrite, n: change the path)"),
+                    ),
+                confirmation_area,
+            );
+            table_area
+        } else {
+            area
+        };
+
+        let visible_logs = self.visible_logs();
+        let shown = visible_logs.len();
+        let matches = self.search_matches(&visible_logs);
+        let selected_range = self.selected_range(&visible_logs);
+        let (title, title_style) = self.title(shown, &matches, selected_range.as_ref());
+
+        // 画面に収まる行だけを作る。末尾はFollow行
+        // 枠線とヘッダー（下の余白を含む）の4行を除いた高さ
+        let height = usize::from(area.height.saturating_sub(4)).max(1);
+        let selected = self
+            .table_state
+            .selected()
+            .map(|selected| selected.min(shown));
+        let offset = scroll_offset(self.table_state.offset().min(shown), selected, height);
+        let end = (offset + height).min(shown + 1);
+        let rows: Vec<Row> = (offset..end)
+            .map(|index| {
+                let Some(log) = visible_logs.get(index) else {
+                    return Row::new(
+                        [Line::from("---")]
+                            .into_iter()
+                            .chain(std::iter::repeat_n(Line::from(""), self.columns.len() + 1))
+                            .chain([Line::from("Follow")]),
+                    )
+                    .style(Style::new().fg(Color::Gray));
+                };
+                let is_highlighted = self.saved_logs.contains(&log.message);
+                let in_range = selected_range
+                    .as_ref()
+                    .is_some_and(|range| range.contains(&index));
+                let content_line = match &self.search {
+                    Some(search) => search.highlight(
+                        log.line.clone(),
+                        Style::new().bg(Color::Cyan).fg(Color::Black),
+                    ),
+                    None => log.line.clone(),
+                };
+                let level_style = log
+                    .level
+                    .map_or(Style::new(), |level| self.level_style(level));
+                let mut cells = vec![
+                    Line::from(get_diff(log.message.datetime)),
+                    Line::from(log.level.map_or("", |level| level.label())),
+                ];
+                cells.extend(
+                    self.column_values(log)
+                        .into_iter()
+                        .zip(&self.columns)
+                        .map(|(value, column)| Line::from(value).alignment(column.align.into())),
+                );
+                cells.push(content_line);
+                Row::new(cells).style(if in_range {
+                    level_style.bg(Color::Blue)
+                } else if is_highlighted {
+                    level_style.bg(Color::Yellow)
+                } else {
+                    level_style
+                })
             })
-            .chain(std::iter::once(
-                Row::new(vec![Line::from("---"), Line::from("Follow")])
-                    .style(Style::new().fg(Color::Gray)),
-            ));
-        let table = Table::new(
-            rows,
-            vec![Constraint::Length(3), Constraint::Percentage(100)],
-        )
-        .header(
-            Row::new(vec!["Tim", "Log"])
+            .collect();
+        let widths = [Constraint::Length(3), Constraint::Length(5)]
+            .into_iter()
+            .chain(
+                self.columns
+                    .iter()
+                    .map(|column| Constraint::Length(column.width)),
+            )
+            .chain([Constraint::Percentage(100)]);
+        let headers = ["Tim".to_string(), "Lvl".to_string()]
+            .into_iter()
+            .chain(self.columns.iter().map(ColumnConfig::header))
+            .chain(["Log".to_string()]);
+        let table = Table::new(rows, widths).header(
+            Row::new(headers)
                 .style(Style::new().bold())
                 .bottom_margin(1),
         );
 
+        // 作った行の中での位置で描画し、全体での位置を覚えておく
+        let mut table_state =
+            TableState::default().with_selected(selected.map(|selected| selected - offset));
         frame.render_stateful_widget(
             table
                 .row_highlight_style(Style::new().reversed())
                 .highlight_symbol(">")
-                .block(Block::bordered().title("Log Stream")),
+                .block(Block::bordered().title(Span::styled(title, title_style))),
             area,
-            &mut self.table_state,
+            &mut table_state,
         );
+        self.table_state.select(selected);
+        *self.table_state.offset_mut() = offset;
+        self.draw_export_menu(frame, area, shown);
         Ok(())
     }
 }
 
-pub fn convert_to_line(raw_text: &str) -> Line<'static> {
-    let result: Result<Value, _> = serde_json::from_str(raw_text);
-
-    if result.is_err() {
-        return Line::from(raw_text.to_string());
+/// First row to draw so that the selected row stays in view, scrolling like `Table` does.
+fn scroll_offset(offset: usize, selected: Option<usize>, height: usize) -> usize {
+    match selected {
+        Some(selected) if selected < offset => selected,
+        Some(selected) if selected >= offset + height => selected + 1 - height,
+        _ => offset,
     }
+}
 
-    let value = result.unwrap();
-    if !value.is_object() {
-        return Line::from(raw_text.to_string());
+/// The content as drawn in the Log column, or only its `message` field with `message_only`.
+fn content_line(content: &str, json: Option<&Value>, message_only: bool) -> Line<'static> {
+    if message_only
+        && let Some(Value::Object(object)) = json
+        && let Some(value) = object.get("message")
+    {
+        return match value {
+            Value::String(text) => convert_to_line(text),
+            value => json_to_line(&value.to_string(), Some(value)),
+        };
     }
+    json_to_line(content, json)
+}
+
+pub fn convert_to_line(raw_text: &str) -> Line<'static> {
+    json_to_line(raw_text, serde_json::from_str(raw_text).ok().as_ref())
+}
 
-    let obj = value.as_object().unwrap();
+/// `json` is `raw_text` already parsed as JSON, if it is.
+fn json_to_line(raw_text: &str, json: Option<&Value>) -> Line<'static> {
+    let Some(Value::Object(obj)) = json else {
+        return Line::from(raw_text.to_string());
+    };
     if !obj.contains_key("message") {
         return Line::from(raw_text.to_string());
     }
@@ -267,10 +1579,10 @@ pub fn convert_to_line(raw_text: &str) -> Line<'static> {
     }
 
     // 最後のカンマを削除
-    if let Some(last_span) = spans.last_mut() {
-        if last_span.content.ends_with(",") {
-            *last_span = Span::raw(last_span.content.trim_end_matches(",").to_string());
-        }
+    if let Some(last_span) = spans.last_mut()
+        && last_span.content.ends_with(",")
+    {
+        *last_span = Span::raw(last_span.content.trim_end_matches(",").to_string());
     }
 
     spans.push(Span::raw("}"));
@@ -327,6 +1639,11 @@ mod test {
     use std::collections::VecDeque;
 
     use super::*;
+    use crate::components::outer_layout::message;
+
+    fn received(content: &str) -> ReceivedLog {
+        ReceivedLog::new(message(content), false)
+    }
 
     #[test]
     fn test_dequeue() {
@@ -351,9 +1668,865 @@ mod test {
         assert_eq!(a[1], 7);
     }
 
+    #[test]
+    fn test_export_received_logs() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        let new_messages = ["first", "second"].into_iter().map(message).collect();
+        log_stream
+            .update(
+                Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog { new_messages })),
+                tx.clone(),
+            )
+            .unwrap();
+        assert_eq!(log_stream.received_logs.len(), 2);
+
+        log_stream.table_state.select(Some(1));
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Enter.into(), tx.clone())
+            .unwrap();
+
+        let mut exported = Vec::new();
+        log_stream
+            .write_logs(&mut exported, ExportFormat::Ndjson, &log_stream.saved_logs)
+            .unwrap();
+        let exported: Vec<Value> = String::from_utf8(exported)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0]["message"], "second");
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char('e').into(), tx.clone())
+            .unwrap();
+        assert!(log_stream.is_editing());
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Esc.into(), tx)
+            .unwrap();
+        assert!(!log_stream.is_editing());
+    }
+
+    #[test]
+    fn test_copy_without_url() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        let new_messages = vec![message("local")];
+        log_stream
+            .update(
+                Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog { new_messages })),
+                tx.clone(),
+            )
+            .unwrap();
+        while rx.try_recv().is_ok() {}
+
+        log_stream.table_state.select(Some(0));
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char('c').into(), tx)
+            .unwrap();
+        assert!(matches!(rx.try_recv(), Ok(Action::Error(e)) if e.contains("no console URL")));
+    }
+
+    #[test]
+    fn test_select_profile_clears_logs() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        log_stream.received_logs.push_back(received("old account"));
+        log_stream.refresh_visible();
+        log_stream.pending_logs.push(message("queued"));
+        log_stream.saved_logs.push(message("saved"));
+        log_stream.filter_pattern = Some("ERROR".to_string());
+
+        log_stream
+            .update(
+                Action::ComponentAction(Box::new(SelectProfile {
+                    profile: "other".to_string(),
+                })),
+                tx,
+            )
+            .unwrap();
+        assert!(log_stream.received_logs.is_empty());
+        assert!(log_stream.pending_logs.is_empty());
+        assert_eq!(log_stream.filter_pattern, None);
+        assert_eq!(log_stream.saved_logs.len(), 1);
+    }
+
+    #[test]
+    fn test_export_to_path() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.config.data_dir = data_dir.path().to_path_buf();
+        config.config.export_filename = "{log_group}.{ext}".to_string();
+        let mut log_stream = LogStream::default();
+        log_stream.register_config_handler(config).unwrap();
+        log_stream.saved_logs.push(Message {
+            log_group: "/aws/lambda/app".to_string(),
+            ..message("first")
+        });
+
+        let export_path = data_dir.path().join("exports").join("aws_lambda_app.csv");
+        let press = |log_stream: &mut LogStream, code: crossterm::event::KeyCode| {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        };
+        press(&mut log_stream, crossterm::event::KeyCode::Char('e'));
+        press(&mut log_stream, crossterm::event::KeyCode::Char('1'));
+        press(&mut log_stream, crossterm::event::KeyCode::Char('3'));
+        let (prompt, input) = log_stream.prompt.as_ref().unwrap();
+        assert_eq!(*prompt, Prompt::ExportPath(ExportFormat::Csv));
+        assert_eq!(input.value(), export_path.display().to_string());
+        press(&mut log_stream, crossterm::event::KeyCode::Enter);
+        assert!(!log_stream.is_editing());
+        assert!(
+            fs::read_to_string(&export_path)
+                .unwrap()
+                .starts_with("timestamp,")
+        );
+
+        // 既存のファイルは確認してから上書きする
+        log_stream.saved_logs[0].content = "second".to_string();
+        press(&mut log_stream, crossterm::event::KeyCode::Char('e'));
+        press(&mut log_stream, crossterm::event::KeyCode::Char('1'));
+        press(&mut log_stream, crossterm::event::KeyCode::Char('3'));
+        press(&mut log_stream, crossterm::event::KeyCode::Enter);
+        assert_eq!(
+            log_stream.overwrite_confirmation,
+            Some((ExportFormat::Csv, export_path.clone()))
+        );
+        press(&mut log_stream, crossterm::event::KeyCode::Char('n'));
+        assert!(log_stream.prompt.is_some());
+        press(&mut log_stream, crossterm::event::KeyCode::Enter);
+        press(&mut log_stream, crossterm::event::KeyCode::Char('y'));
+        assert!(!log_stream.is_editing());
+        assert!(
+            fs::read_to_string(&export_path)
+                .unwrap()
+                .contains(",second,")
+        );
+    }
+
+    #[test]
+    fn test_export_scopes() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut log_stream = LogStream::default();
+        let mut config = Config::default();
+        config.config.data_dir = data_dir.path().to_path_buf();
+        config.config.scrollback = 3;
+        log_stream.register_config_handler(config).unwrap();
+
+        let now = Utc::now();
+        let messages: Vec<Message> = (0..5)
+            .map(|index| Message {
+                datetime: now - chrono::Duration::minutes(5 - index),
+                ..message(&format!(
+                    "{} log {index}",
+                    if index % 2 == 0 { "INFO" } else { "ERROR" }
+                ))
+            })
+            .collect();
+        log_stream
+            .update(
+                Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog {
+                    new_messages: messages.clone(),
+                })),
+                tx.clone(),
+            )
+            .unwrap();
+        assert_eq!(log_stream.scrollback.len(), 2);
+
+        // ディスクに書き出したログも含める
+        assert_eq!(
+            log_stream
+                .collect_export_logs(&ExportScope::Buffered)
+                .unwrap(),
+            messages
+        );
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char('3').into(), tx.clone())
+            .unwrap();
+        assert_eq!(
+            log_stream
+                .collect_export_logs(&ExportScope::Visible)
+                .unwrap(),
+            vec![messages[3].clone()]
+        );
+
+        for code in [
+            crossterm::event::KeyCode::Char('e'),
+            crossterm::event::KeyCode::Char('4'),
+        ]
+        .into_iter()
+        .chain("-150s".chars().map(crossterm::event::KeyCode::Char))
+        .chain([crossterm::event::KeyCode::Enter])
+        {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        assert!(matches!(
+            log_stream.export_menu,
+            Some(ExportMenu::Format(_))
+        ));
+        let (scope, logs) = log_stream.export_logs.as_ref().unwrap();
+        assert_eq!(scope.to_string(), "-150s");
+        assert_eq!(logs, &messages[3..]);
+    }
+
+    #[test]
+    fn test_visual_range_selection() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        let new_messages: Vec<Message> = (0..5)
+            .map(|index| message(&format!("log {index}")))
+            .collect();
+        log_stream
+            .update(
+                Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog {
+                    new_messages: new_messages.clone(),
+                })),
+                tx.clone(),
+            )
+            .unwrap();
+
+        log_stream.table_state.select(Some(1));
+        let shift_down = crossterm::event::KeyEvent::new(
+            crossterm::event::KeyCode::Down,
+            crossterm::event::KeyModifiers::SHIFT,
+        );
+        log_stream.handle_key_event(shift_down, tx.clone()).unwrap();
+        log_stream.handle_key_event(shift_down, tx.clone()).unwrap();
+        assert_eq!(
+            log_stream.selected_range(&log_stream.visible_logs()),
+            Some(1..=3)
+        );
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Enter.into(), tx.clone())
+            .unwrap();
+        assert_eq!(log_stream.saved_logs, new_messages[1..=3]);
+        assert_eq!(log_stream.selected_range(&log_stream.visible_logs()), None);
+
+        // すべて保存済みの範囲は保存を解除する
+        for code in [
+            crossterm::event::KeyCode::Char('v'),
+            crossterm::event::KeyCode::Up,
+            crossterm::event::KeyCode::Enter,
+        ] {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        assert_eq!(log_stream.saved_logs, new_messages[1..=1]);
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char('v').into(), tx.clone())
+            .unwrap();
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Esc.into(), tx)
+            .unwrap();
+        assert_eq!(log_stream.range_anchor, None);
+    }
+
+    #[test]
+    fn test_filter_prompt() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+
+        for code in [
+            crossterm::event::KeyCode::Char('f'),
+            crossterm::event::KeyCode::Char('?'),
+            crossterm::event::KeyCode::Char('E'),
+            crossterm::event::KeyCode::Char('R'),
+            crossterm::event::KeyCode::Enter,
+        ] {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+
+        assert!(!log_stream.is_editing());
+        assert_eq!(log_stream.filter_pattern, Some("?ER".to_string()));
+        assert_eq!(rx.try_recv().unwrap(), Action::ChangeMode(Mode::Input));
+        assert_eq!(rx.try_recv().unwrap(), Action::ChangeMode(Mode::Home));
+        let Ok(Action::ComponentAction(action)) = rx.try_recv() else {
+            panic!("expected ChangeFilterPattern");
+        };
+        assert_eq!(
+            action.as_any().downcast_ref::<ChangeFilterPattern>(),
+            Some(&ChangeFilterPattern {
+                filter_pattern: Some("?ER".to_string())
+            })
+        );
+    }
+
+    #[test]
+    fn test_time_range_prompt() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+
+        for code in "h-1d".chars().map(crossterm::event::KeyCode::Char) {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Enter.into(), tx.clone())
+            .unwrap();
+
+        assert!(!log_stream.is_editing());
+        assert_eq!(
+            log_stream.time_range.as_ref().map(|t| t.to_string()),
+            Some("-1d".to_string())
+        );
+        let action = std::iter::from_fn(|| rx.try_recv().ok())
+            .find_map(|action| match action {
+                Action::ComponentAction(action) => {
+                    action.as_any().downcast_ref::<ChangeTimeRange>().cloned()
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(action.time_range, log_stream.time_range);
+
+        // 不正な入力はエラーを送ってプロンプトを開いたままにする
+        for code in [
+            crossterm::event::KeyCode::Char('h'),
+            crossterm::event::KeyCode::Char('x'),
+            crossterm::event::KeyCode::Enter,
+        ] {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        assert!(log_stream.is_editing());
+        assert!(matches!(
+            std::iter::from_fn(|| rx.try_recv().ok()).last(),
+            Some(Action::Error(_))
+        ));
+    }
+
+    #[test]
+    fn test_search_pages_replace_logs() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        log_stream.received_logs.push_back(received("live"));
+        log_stream.refresh_visible();
+
+        let actions: Vec<Box<dyn ComponentAction>> = vec![
+            Box::new(SearchEvents { first_page: true }),
+            Box::new(ReceiveEventsPage {
+                messages: vec![message("old")],
+                next_requests: vec![Default::default()],
+            }),
+        ];
+        for action in actions {
+            log_stream
+                .update(Action::ComponentAction(action), tx.clone())
+                .unwrap();
+        }
+
+        let contents: Vec<_> = log_stream
+            .received_logs
+            .iter()
+            .map(|log| log.message.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["old"]);
+        assert!(log_stream.has_more_events);
+        assert!(!log_stream.searching);
+    }
+
+    #[test]
+    fn test_incremental_search() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        log_stream.received_logs.extend(
+            ["Error: a", "ok", "error: b", "ok", "ERROR: c"]
+                .into_iter()
+                .map(received),
+        );
+        log_stream.refresh_visible();
+        log_stream.table_state.select(Some(1));
+
+        for code in "/error".chars().map(crossterm::event::KeyCode::Char) {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        // 入力中に選択位置から次のマッチへ移動する
+        assert_eq!(log_stream.table_state.selected(), Some(2));
+        assert_eq!(
+            log_stream.search_matches(&log_stream.visible_logs()),
+            vec![2]
+        );
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Tab.into(), tx.clone())
+            .unwrap();
+        assert_eq!(
+            log_stream.search_matches(&log_stream.visible_logs()),
+            vec![0, 2, 4]
+        );
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Enter.into(), tx.clone())
+            .unwrap();
+        assert!(!log_stream.is_editing());
+
+        for (code, expected) in [('n', 4), ('n', 0), ('N', 4), ('N', 2)] {
+            log_stream
+                .handle_key_event(crossterm::event::KeyCode::Char(code).into(), tx.clone())
+                .unwrap();
+            assert_eq!(log_stream.table_state.selected(), Some(expected));
+        }
+        let (title, _) = log_stream.title(
+            5,
+            &log_stream.search_matches(&log_stream.visible_logs()),
+            None,
+        );
+        assert!(title.contains("[/error (ignore case) 2/3]"));
+
+        // キャンセルすると元の検索と選択位置に戻る
+        for code in [
+            crossterm::event::KeyCode::Char('/'),
+            crossterm::event::KeyCode::Char('o'),
+            crossterm::event::KeyCode::Char('k'),
+            crossterm::event::KeyCode::Esc,
+        ] {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        assert_eq!(log_stream.table_state.selected(), Some(2));
+        assert_eq!(
+            log_stream.search_matches(&log_stream.visible_logs()),
+            vec![0, 2, 4]
+        );
+    }
+
+    #[test]
+    fn test_display_filter_keeps_following() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        let receive = |log_stream: &mut LogStream, contents: &[&str]| {
+            let new_messages = contents.iter().copied().map(message).collect();
+            log_stream
+                .update(
+                    Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog { new_messages })),
+                    tx.clone(),
+                )
+                .unwrap();
+        };
+        receive(
+            &mut log_stream,
+            &[r#"{"level":"INFO"}"#, r#"{"level":"ERROR"}"#],
+        );
+        // Follow行を選択する
+        log_stream.table_state.select(Some(2));
+
+        for code in "Flevel=error"
+            .chars()
+            .map(crossterm::event::KeyCode::Char)
+            .chain([crossterm::event::KeyCode::Enter])
+        {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        assert_eq!(log_stream.visible_logs().len(), 1);
+        assert!(log_stream.is_follow_log());
+        let (title, _) = log_stream.title(1, &[], None);
+        assert!(title.contains("[display: level=error, 1 of 2 shown]"));
+
+        receive(
+            &mut log_stream,
+            &[r#"{"level":"ERROR","n":2}"#, r#"{"level":"INFO"}"#],
+        );
+        assert_eq!(log_stream.visible_logs().len(), 2);
+        assert!(log_stream.is_follow_log());
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Up.into(), tx.clone())
+            .unwrap();
+        assert_eq!(
+            log_stream.get_selected_log().unwrap().content,
+            r#"{"level":"ERROR","n":2}"#
+        );
+
+        // 無効な式はプロンプトを開いたままにする
+        for code in "F and"
+            .chars()
+            .map(crossterm::event::KeyCode::Char)
+            .chain([crossterm::event::KeyCode::Enter])
+        {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+        assert!(log_stream.is_editing());
+    }
+
+    #[test]
+    fn test_scrollback_pages_older_logs_in() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut log_stream = LogStream::default();
+        let mut config = Config::default();
+        config.config.data_dir = data_dir.path().to_path_buf();
+        config.config.scrollback = 300;
+        log_stream.register_config_handler(config).unwrap();
+
+        let messages: Vec<Message> = (0..1000)
+            .map(|index| message(&format!("log {index}")))
+            .collect();
+        for chunk in messages.chunks(100) {
+            log_stream
+                .update(
+                    Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog {
+                        new_messages: chunk.to_vec(),
+                    })),
+                    tx.clone(),
+                )
+                .unwrap();
+        }
+        assert_eq!(log_stream.received_logs.len(), 300);
+        assert_eq!(log_stream.scrollback.len(), 700);
+
+        // 先頭でさらに上に移動するとディスクから読み戻す
+        log_stream.table_state.select(Some(0));
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Up.into(), tx.clone())
+            .unwrap();
+        assert_eq!(log_stream.received_logs.len(), 300 + SCROLLBACK_PAGE);
+        assert_eq!(log_stream.get_selected_log(), Some(&messages[699]));
+
+        // 読み戻したログは次に追い出されても重複して書き込まれない
+        log_stream
+            .update(
+                Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog {
+                    new_messages: vec![messages[0].clone()],
+                })),
+                tx.clone(),
+            )
+            .unwrap();
+        assert_eq!(log_stream.scrollback.len(), 701);
+        assert_eq!(log_stream.first_number, 701);
+        assert_eq!(log_stream.received_logs.len(), 300);
+    }
+
+    #[test]
+    fn test_visible_rows_follow_eviction() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut log_stream = LogStream::default();
+        let mut config = Config::default();
+        config.config.data_dir = data_dir.path().to_path_buf();
+        config.config.scrollback = 300;
+        log_stream.register_config_handler(config).unwrap();
+        for code in "Flevel=error"
+            .chars()
+            .map(crossterm::event::KeyCode::Char)
+            .chain([crossterm::event::KeyCode::Enter])
+        {
+            log_stream
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+
+        let messages: Vec<Message> = (0..1000)
+            .map(|index| {
+                let level = if index % 3 == 0 { "ERROR" } else { "INFO" };
+                message(&format!(r#"{{"level":"{level}","n":{index}}}"#))
+            })
+            .collect();
+        let expected = |log_stream: &LogStream| -> Vec<usize> {
+            (0..log_stream.received_logs.len())
+                .filter(|&index| log_stream.is_visible(&log_stream.received_logs[index]))
+                .collect()
+        };
+        // Follow行を選択する
+        log_stream.table_state.select(Some(0));
+        for chunk in messages.chunks(70) {
+            log_stream
+                .update(
+                    Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog {
+                        new_messages: chunk.to_vec(),
+                    })),
+                    tx.clone(),
+                )
+                .unwrap();
+            assert_eq!(log_stream.visible, expected(&log_stream));
+        }
+        assert!(log_stream.is_follow_log());
+
+        // 読み戻したログも表示フィルターを通して先頭に加える
+        log_stream.table_state.select(Some(0));
+        let first = log_stream.get_selected_log().cloned();
+        log_stream.load_older_logs().unwrap();
+        assert_eq!(log_stream.visible, expected(&log_stream));
+        assert_eq!(log_stream.get_selected_log().cloned(), first);
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char('1').into(), tx.clone())
+            .unwrap();
+        assert!(log_stream.visible.is_empty());
+        assert_eq!(log_stream.visible, expected(&log_stream));
+    }
+
+    #[test]
+    fn test_pause_queues_new_logs() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        let receive = |log_stream: &mut LogStream, content: &str| {
+            log_stream
+                .update(
+                    Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog {
+                        new_messages: vec![message(content)],
+                    })),
+                    tx.clone(),
+                )
+                .unwrap();
+        };
+        receive(&mut log_stream, "first");
+        log_stream.table_state.select(Some(0));
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char(' ').into(), tx.clone())
+            .unwrap();
+        receive(&mut log_stream, "second");
+        receive(&mut log_stream, "third");
+        assert_eq!(log_stream.received_logs.len(), 1);
+        let (title, _) = log_stream.title(1, &[], None);
+        assert!(title.contains("[paused: +2 new]"));
+
+        // 再開するとキューを反映してFollowに戻る
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char(' ').into(), tx.clone())
+            .unwrap();
+        assert_eq!(log_stream.received_logs.len(), 3);
+        assert!(log_stream.pending_logs.is_empty());
+        assert!(log_stream.is_follow_log());
+    }
+
+    #[test]
+    fn test_pause_spills_queue_to_disk() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let dir = tempfile::tempdir().unwrap();
+        let mut log_stream = LogStream::default();
+        let mut config = Config::default();
+        config.config.data_dir = dir.path().to_path_buf();
+        config.config.scrollback = 3;
+        log_stream.register_config_handler(config).unwrap();
+        let receive = |log_stream: &mut LogStream, contents: &[&str]| {
+            log_stream
+                .update(
+                    Action::ComponentAction(Box::new(outer_layout::ReceiveNewLog {
+                        new_messages: contents.iter().copied().map(message).collect(),
+                    })),
+                    tx.clone(),
+                )
+                .unwrap();
+        };
+        let contents = |logs: Vec<Message>| -> Vec<String> {
+            logs.into_iter().map(|log| log.content).collect()
+        };
+
+        receive(&mut log_stream, &["a", "b"]);
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char(' ').into(), tx.clone())
+            .unwrap();
+        receive(&mut log_stream, &["c", "d", "e", "f", "g"]);
+
+        // キューは`scrollback`件までで、あふれた分はディスクに書く
+        assert_eq!(log_stream.received_logs.len(), 2);
+        assert_eq!(log_stream.pending_logs.len(), 3);
+        assert_eq!(log_stream.scrollback.len(), 4);
+        let (title, _) = log_stream.title(2, &[], None);
+        assert!(title.contains("[paused: +5 new]"));
+        assert_eq!(
+            contents(log_stream.buffered_logs().unwrap()),
+            vec!["a", "b", "c", "d", "e", "f", "g"]
+        );
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char(' ').into(), tx.clone())
+            .unwrap();
+        let received: Vec<_> = log_stream
+            .received_logs
+            .iter()
+            .map(|log| log.message.content.as_str())
+            .collect();
+        assert_eq!(received, vec!["e", "f", "g"]);
+        assert_eq!(log_stream.first_number, 4);
+        assert!(log_stream.is_follow_log());
+        assert_eq!(
+            contents(log_stream.buffered_logs().unwrap()),
+            vec!["a", "b", "c", "d", "e", "f", "g"]
+        );
+    }
+
+    #[test]
+    fn test_pause_queues_search_pages() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        log_stream.received_logs.push_back(received("live"));
+        log_stream.refresh_visible();
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char(' ').into(), tx.clone())
+            .unwrap();
+
+        let actions: Vec<Box<dyn ComponentAction>> = vec![
+            Box::new(SearchEvents { first_page: true }),
+            Box::new(ReceiveEventsPage {
+                messages: vec![message("old")],
+                next_requests: Vec::new(),
+            }),
+        ];
+        for action in actions {
+            log_stream
+                .update(Action::ComponentAction(action), tx.clone())
+                .unwrap();
+        }
+        // 止めている間は表示を変えない
+        assert_eq!(log_stream.received_logs.len(), 1);
+        assert_eq!(log_stream.received_logs[0].message.content, "live");
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char(' ').into(), tx.clone())
+            .unwrap();
+        let contents: Vec<_> = log_stream
+            .received_logs
+            .iter()
+            .map(|log| log.message.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["old"]);
+        assert!(!log_stream.clear_on_resume);
+    }
+
+    #[test]
+    fn test_toggle_level_visibility() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        log_stream.received_logs.extend(
+            [
+                r#"{"level":"debug"}"#,
+                "[ERROR] failed",
+                "START RequestId: abc",
+                r#"{"level":"info"}"#,
+            ]
+            .into_iter()
+            .map(received),
+        );
+        log_stream.refresh_visible();
+        log_stream.table_state.select(Some(1));
+
+        // 4: DEBUG, 3: INFO
+        for c in ['4', '3'] {
+            log_stream
+                .handle_key_event(crossterm::event::KeyCode::Char(c).into(), tx.clone())
+                .unwrap();
+        }
+        let contents: Vec<_> = log_stream
+            .visible_logs()
+            .iter()
+            .map(|log| log.message.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["[ERROR] failed", "START RequestId: abc"]);
+        assert_eq!(log_stream.table_state.selected(), Some(0));
+        let (title, _) = log_stream.title(2, &[], None);
+        assert!(title.contains("[display: hide INFO/DEBUG, 2 of 4 shown]"));
+
+        log_stream
+            .handle_key_event(crossterm::event::KeyCode::Char('4').into(), tx.clone())
+            .unwrap();
+        assert_eq!(log_stream.visible_logs().len(), 3);
+        assert_eq!(log_stream.table_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn test_draw_only_visible_rows() {
+        let mut log_stream = LogStream::default();
+        let contents: Vec<String> = (0..50).map(|i| format!("log {i}")).collect();
+        log_stream
+            .received_logs
+            .extend(contents.iter().map(|content| received(content)));
+        log_stream.refresh_visible();
+        log_stream.table_state.select(Some(50));
+
+        // 枠線とヘッダーを除いて6行表示できる
+        let mut terminal = Terminal::new(backend::TestBackend::new(40, 10)).unwrap();
+        let mut draw = |log_stream: &mut LogStream| {
+            terminal
+                .draw(|frame| log_stream.draw(frame, frame.area()).unwrap())
+                .unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..10)
+                .map(|y| (0..40).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let screen = draw(&mut log_stream);
+        assert!(screen.contains("log 45") && screen.contains("log 49"));
+        assert!(screen.contains("Follow"));
+        assert!(!screen.contains("log 44"));
+        assert_eq!(log_stream.table_state.offset(), 45);
+        assert_eq!(log_stream.table_state.selected(), Some(50));
+
+        // 上にはみ出した行を選択するとそこまでスクロールする
+        log_stream.table_state.select(Some(10));
+        let screen = draw(&mut log_stream);
+        assert!(screen.contains("log 10") && screen.contains("log 15"));
+        assert!(!screen.contains("log 16") && !screen.contains("Follow"));
+        assert_eq!(log_stream.table_state.offset(), 10);
+    }
+
+    #[test]
+    fn test_log_group_columns() {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut log_stream = LogStream::default();
+        let mut config = Config::default();
+        config.config.columns = json5::from_str(r#"[{ path: "$.requestId" }]"#).unwrap();
+        config.config.log_groups = json5::from_str(
+            r#"[{
+                log_group: "api",
+                columns: [{ path: "$.http.status" }, { path: "requestId" }],
+                message_only: true,
+            }]"#,
+        )
+        .unwrap();
+        log_stream.register_config_handler(config).unwrap();
+        log_stream
+            .push_logs(&[message(
+                r#"{"requestId":"abc","http":{"status":500},"message":"failed"}"#,
+            )])
+            .unwrap();
+        assert_eq!(
+            log_stream.column_values(&log_stream.received_logs[0]),
+            vec!["abc"]
+        );
+
+        log_stream
+            .update(
+                Action::ComponentAction(Box::new(SelectLogGroup {
+                    log_groups: vec![LogGroup {
+                        name: "api".to_string(),
+                        ..Default::default()
+                    }],
+                })),
+                tx,
+            )
+            .unwrap();
+        // 受信済みのログもmessageだけの表示にする
+        let log = &log_stream.received_logs[0];
+        assert_eq!(log_stream.column_values(log), vec!["abc", "500"]);
+        assert_eq!(log.line, Line::from("failed"));
+    }
+
     #[test]
     fn test_convert_to_line() {
-        let a = convert_to_line(
+        let line = convert_to_line(
             r#"
 {
     "cold_start": true,
@@ -374,5 +2547,6 @@ mod test {
 }
         "#,
         );
+        assert!(!line.spans.is_empty());
     }
 }
@@ -0,0 +1,409 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::debug;
+
+use super::{
+    Component,
+    log_group_list::{LogGroup, SelectLogGroup},
+    profile_picker::SelectProfile,
+    text_input::{InputOutcome, TextInput},
+};
+use crate::{
+    action::{Action, ComponentAction},
+    app::Mode,
+    backend::LogBackend,
+    date::get_diff,
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogStreamSummary {
+    pub name: String,
+    pub last_event_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct FetchLogStreams {
+    log_group_name: String,
+    log_streams: Vec<LogStreamSummary>,
+}
+impl ComponentAction for FetchLogStreams {
+    fn name(&self) -> &'static str {
+        "FetchLogStreams"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectLogStreams {
+    pub log_stream_names: Vec<String>,
+    pub log_stream_name_prefixes: Vec<String>,
+}
+impl ComponentAction for SelectLogStreams {
+    fn name(&self) -> &'static str {
+        "SelectLogStreams"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Streams of the selected log group.
+///
+/// Live Tail only accepts stream names or prefixes when a single log group is tailed, so the list
+/// stays empty while zero or several groups are selected.
+#[derive(Clone, Debug)]
+pub struct LogStreamList {
+    backend: Arc<dyn LogBackend>,
+    log_group: Option<LogGroup>,
+    loaded_log_streams: Vec<LogStreamSummary>,
+    table_state: TableState,
+
+    selected_log_streams: BTreeSet<String>,
+    log_stream_name_prefixes: Vec<String>,
+    prefix_input: Option<TextInput>,
+}
+
+impl LogStreamList {
+    pub fn new(backend: Arc<dyn LogBackend>) -> Self {
+        Self {
+            backend,
+            log_group: None,
+            loaded_log_streams: Vec::new(),
+            table_state: TableState::default(),
+            selected_log_streams: BTreeSet::new(),
+            log_stream_name_prefixes: Vec::new(),
+            prefix_input: None,
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.prefix_input.is_some()
+    }
+
+    fn fetch_log_streams(&self, log_group_name: String, tx: UnboundedSender<Action>) {
+        let backend = self.backend.clone();
+        tokio::spawn(async move {
+            let log_streams = match backend.list_log_streams(log_group_name.clone()).await {
+                Ok(log_streams) => log_streams,
+                Err(e) => {
+                    debug!("Failed to list log streams: {}", e);
+                    let _ = tx.send(Action::Error(format!("Failed to list log streams: {e:#}")));
+                    return;
+                }
+            };
+
+            tx.send(Action::ComponentAction(Box::new(FetchLogStreams {
+                log_group_name,
+                log_streams,
+            })))
+            .unwrap_or_else(|e| {
+                debug!("Failed to send FetchLogStreams action: {}", e);
+            })
+        });
+    }
+
+    fn send_selection(&self, tx: &UnboundedSender<Action>) -> Result<()> {
+        tx.send(Action::ComponentAction(Box::new(SelectLogStreams {
+            log_stream_names: self.selected_log_streams.iter().cloned().collect(),
+            log_stream_name_prefixes: self.log_stream_name_prefixes.clone(),
+        })))?;
+        Ok(())
+    }
+
+    fn handle_prefix_input(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        let Some(prefix_input) = &mut self.prefix_input else {
+            return Ok(());
+        };
+        match prefix_input.handle_key_event(key) {
+            InputOutcome::Pending => {}
+            InputOutcome::Submit(value) => {
+                // 名前指定とプレフィックス指定は同時に使えない
+                self.log_stream_name_prefixes = value
+                    .split([',', ' '])
+                    .filter(|prefix| !prefix.is_empty())
+                    .map(|prefix| prefix.to_string())
+                    .collect();
+                self.selected_log_streams.clear();
+                self.prefix_input = None;
+                tx.send(Action::ChangeMode(Mode::Home))?;
+                self.send_selection(&tx)?;
+            }
+            InputOutcome::Cancel => {
+                self.prefix_input = None;
+                tx.send(Action::ChangeMode(Mode::Home))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Component for LogStreamList {
+    fn handle_key_event(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        if self.is_editing() {
+            return self.handle_prefix_input(key, tx);
+        }
+        if self.log_group.is_none() {
+            return Ok(());
+        }
+
+        match key.code {
+            crossterm::event::KeyCode::Up | crossterm::event::KeyCode::PageUp => {
+                self.table_state.scroll_up_by(1);
+            }
+            crossterm::event::KeyCode::Down | crossterm::event::KeyCode::PageDown => {
+                self.table_state.scroll_down_by(1);
+            }
+            crossterm::event::KeyCode::Enter => {
+                if let Some(log_stream) = self
+                    .table_state
+                    .selected()
+                    .and_then(|index| self.loaded_log_streams.get(index))
+                {
+                    if !self.selected_log_streams.remove(&log_stream.name) {
+                        self.selected_log_streams.insert(log_stream.name.clone());
+                    }
+                    self.log_stream_name_prefixes.clear();
+                    self.send_selection(&tx)?;
+                }
+            }
+            crossterm::event::KeyCode::Char('p') => {
+                self.prefix_input = Some(TextInput::new(&self.log_stream_name_prefixes.join(",")));
+                tx.send(Action::ChangeMode(Mode::Input))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, action: Action, tx: UnboundedSender<Action>) -> Result<()> {
+        let Action::ComponentAction(component_action) = action else {
+            return Ok(());
+        };
+
+        if component_action
+            .as_any()
+            .downcast_ref::<SelectProfile>()
+            .is_some()
+        {
+            // 別アカウントのストリームは選べない
+            self.loaded_log_streams.clear();
+            self.selected_log_streams.clear();
+            self.log_stream_name_prefixes.clear();
+            self.prefix_input = None;
+            self.table_state = TableState::default();
+            self.log_group = None;
+        } else if let Some(select_action) =
+            component_action.as_any().downcast_ref::<SelectLogGroup>()
+        {
+            let log_group = match select_action.log_groups.as_slice() {
+                [log_group] => Some(log_group.clone()),
+                _ => None,
+            };
+
+            self.loaded_log_streams.clear();
+            self.selected_log_streams.clear();
+            self.log_stream_name_prefixes.clear();
+            self.table_state = TableState::default();
+            if let Some(log_group) = &log_group {
+                self.fetch_log_streams(log_group.name.clone(), tx);
+            }
+            self.log_group = log_group;
+        } else if let Some(fetch_action) =
+            component_action.as_any().downcast_ref::<FetchLogStreams>()
+        {
+            // 取得中にロググループが変わった場合は破棄
+            if self.log_group.as_ref().map(|lg| &lg.name) == Some(&fetch_action.log_group_name) {
+                self.loaded_log_streams = fetch_action.log_streams.clone();
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        let area = if let Some(prefix_input) = &self.prefix_input {
+            let [table_area, input_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(area);
+            prefix_input.draw(
+                frame,
+                input_area,
+                "Stream name prefixes, comma separated (Enter: apply, Esc: cancel)",
+            );
+            table_area
+        } else {
+            area
+        };
+
+        let Some(log_group) = &self.log_group else {
+            frame.render_widget(
+                Paragraph::new("Select exactly one log group to choose streams")
+                    .style(Style::new().fg(Color::Gray))
+                    .block(Block::bordered().title("Log Streams")),
+                area,
+            );
+            return Ok(());
+        };
+
+        let mut title = format!("Log Streams: {}", log_group.name);
+        if !self.log_stream_name_prefixes.is_empty() {
+            title.push_str(&format!(
+                " [prefix: {}]",
+                self.log_stream_name_prefixes.join(",")
+            ));
+        }
+
+        let rows = self.loaded_log_streams.iter().map(|log_stream| {
+            let is_highlighted = self.selected_log_streams.contains(&log_stream.name);
+            Row::new(vec![
+                log_stream
+                    .last_event_time
+                    .map(get_diff)
+                    .unwrap_or_else(|| "-".to_string()),
+                log_stream.name.clone(),
+            ])
+            .style(if is_highlighted {
+                Style::new().bg(Color::Yellow)
+            } else {
+                Style::new()
+            })
+        });
+        let table = Table::new(
+            rows,
+            vec![Constraint::Length(3), Constraint::Percentage(100)],
+        )
+        .header(
+            Row::new(vec!["Lst", "LogStream"])
+                .style(Style::new().bold())
+                .bottom_margin(1),
+        );
+
+        frame.render_stateful_widget(
+            table
+                .row_highlight_style(Style::new().reversed())
+                .highlight_symbol(">")
+                .block(Block::bordered().title(title)),
+            area,
+            &mut self.table_state,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::backend::fake::FakeLogBackend;
+
+    fn select_log_group(log_stream_list: &mut LogStreamList, tx: UnboundedSender<Action>) {
+        let select_action = SelectLogGroup {
+            log_groups: vec![LogGroup {
+                name: "/ecs/cart".to_string(),
+                ..Default::default()
+            }],
+        };
+        log_stream_list
+            .update(Action::ComponentAction(Box::new(select_action)), tx)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_select_log_streams() {
+        let log_streams = ["ecs/cart/task-2", "ecs/cart/task-1"]
+            .into_iter()
+            .map(|name| LogStreamSummary {
+                name: name.to_string(),
+                last_event_time: Some(Utc::now()),
+            })
+            .collect();
+        let backend = Arc::new(FakeLogBackend::new().log_streams(log_streams));
+        let mut log_stream_list = LogStreamList::new(backend);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        select_log_group(&mut log_stream_list, tx.clone());
+        let Some(fetch_action) = rx.recv().await else {
+            panic!("expected FetchLogStreams");
+        };
+        log_stream_list.update(fetch_action, tx.clone()).unwrap();
+        assert_eq!(log_stream_list.loaded_log_streams.len(), 2);
+
+        log_stream_list
+            .handle_key_event(crossterm::event::KeyCode::Down.into(), tx.clone())
+            .unwrap();
+        log_stream_list
+            .handle_key_event(crossterm::event::KeyCode::Enter.into(), tx.clone())
+            .unwrap();
+        let Ok(Action::ComponentAction(action)) = rx.try_recv() else {
+            panic!("expected SelectLogStreams");
+        };
+        assert_eq!(
+            action.as_any().downcast_ref::<SelectLogStreams>(),
+            Some(&SelectLogStreams {
+                log_stream_names: vec!["ecs/cart/task-1".to_string()],
+                log_stream_name_prefixes: Vec::new(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prefix_replaces_selected_names() {
+        let backend = Arc::new(FakeLogBackend::new());
+        let mut log_stream_list = LogStreamList::new(backend);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        select_log_group(&mut log_stream_list, tx.clone());
+        log_stream_list
+            .selected_log_streams
+            .insert("ecs/cart/task-1".to_string());
+
+        for code in [
+            crossterm::event::KeyCode::Char('p'),
+            crossterm::event::KeyCode::Char('a'),
+            crossterm::event::KeyCode::Char(','),
+            crossterm::event::KeyCode::Char('b'),
+            crossterm::event::KeyCode::Enter,
+        ] {
+            log_stream_list
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        }
+
+        assert!(log_stream_list.selected_log_streams.is_empty());
+        let select_action = std::iter::from_fn(|| rx.try_recv().ok())
+            .find_map(|action| match action {
+                Action::ComponentAction(action) => {
+                    action.as_any().downcast_ref::<SelectLogStreams>().cloned()
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            select_action.log_stream_name_prefixes,
+            vec!["a".to_string(), "b".to_string()]
+        );
+    }
+}
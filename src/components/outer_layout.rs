@@ -1,5 +1,6 @@
-use chrono::DateTime;
-use chrono_tz::Tz;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
 use color_eyre::Result;
 use ratatui::prelude::*;
 use serde::{Serialize, Serializer};
@@ -10,20 +11,34 @@ use ulid::Ulid;
 
 use super::{
     Component,
+    error_panel::ErrorPanel,
+    insights::Insights,
     log_group_list::{self, LogGroupList},
-    log_stream::LogStream,
+    log_stream::{
+        ChangeFilterPattern, ChangeTimeRange, FetchNextPage, LogStream, ReconnectLiveTail,
+    },
+    log_stream_list::{LogStreamList, SelectLogStreams},
+    profile_picker::{ProfilePicker, SelectProfile},
+    toast::Toasts,
 };
 use crate::{
     action::{Action, ComponentAction},
+    backend::{FetchEventsRequest, LiveTailRequest, LogBackend},
     components::log_detail::LogDetail,
     config::Config,
+    date::TimeRange,
+    history::{self, ReceiveEventsPage, SearchEvents},
+    live_tail::{self, ChangeLiveTailStatus, LiveTailStatus},
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Message {
     pub id: Ulid,
     pub content: String,
-    pub datetime: DateTime<Tz>,
+    pub datetime: DateTime<Utc>,
+    /// Log group name without the account ID
+    pub log_group: String,
+    pub log_stream: String,
     pub url: String,
 }
 impl Serialize for Message {
@@ -36,21 +51,45 @@ impl Serialize for Message {
 }
 
 impl Message {
-    fn generate_url(log_group_identifier: &str, log_stream_name: &str) -> String {
-        // NOTE: アカウントID部分は除外
-        let log_group_id_without_account = &log_group_identifier[13..];
+    pub fn generate_url(region: &str, log_group_identifier: &str, log_stream_name: &str) -> String {
+        let log_group_id_without_account = log_group_name(log_group_identifier);
 
         format!(
             "https://{}.console.aws.amazon.com/cloudwatch/home?region={}#logsV2:log-groups/log-group/{}/log-events/{}",
-            AWS_REGION,
-            AWS_REGION,
+            region,
+            region,
             urlencoding::encode(&urlencoding::encode(log_group_id_without_account)),
             urlencoding::encode(&urlencoding::encode(log_stream_name))
         )
     }
 }
 
-const AWS_REGION: &str = "ap-northeast-1";
+/// Message with the given content and no log group, stream or URL, for tests.
+#[cfg(test)]
+pub fn message(content: &str) -> Message {
+    Message {
+        id: Ulid::new(),
+        content: content.to_string(),
+        datetime: Utc::now(),
+        log_group: String::new(),
+        log_stream: String::new(),
+        url: String::new(),
+    }
+}
+
+/// Extract the log group name from an identifier.
+///
+/// Live tail reports groups as `<account id>:<name>` and the API also accepts ARNs, so both are
+/// reduced to the bare name that the console URL expects.
+pub fn log_group_name(log_group_identifier: &str) -> &str {
+    if let Some((_, rest)) = log_group_identifier.split_once(":log-group:") {
+        return rest.strip_suffix(":*").unwrap_or(rest);
+    }
+    // NOTE: アカウントID部分は除外
+    log_group_identifier
+        .split_once(':')
+        .map_or(log_group_identifier, |(_, name)| name)
+}
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct ReceiveNewLog {
@@ -70,40 +109,66 @@ impl ComponentAction for ReceiveNewLog {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
+#[allow(clippy::enum_variant_names)]
 enum Cursor {
+    #[default]
     LogGroupList,
+    LogStreamList,
     LogStream,
-}
-impl Default for Cursor {
-    fn default() -> Self {
-        Self::LogGroupList
-    }
+    LogDetail,
 }
 
-#[derive(Default, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct OuterLayout {
+    backend: Arc<dyn LogBackend>,
     cursor: Cursor,
     log_group_list: LogGroupList,
+    log_stream_list: LogStreamList,
     log_stream: LogStream,
     log_detail: LogDetail<'static>,
+    profile_picker: ProfilePicker,
+    error_panel: ErrorPanel,
+    toasts: Toasts,
+    insights: Insights,
+    live_tail_request: LiveTailRequest,
+    /// Historical search instead of live tail while set
+    time_range: Option<TimeRange>,
+    next_requests: Vec<FetchEventsRequest>,
     stream_cancel_token: Option<CancellationToken>,
 }
 
 impl OuterLayout {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(backend: Arc<dyn LogBackend>) -> Self {
+        Self {
+            log_group_list: LogGroupList::new(backend.clone()),
+            log_stream_list: LogStreamList::new(backend.clone()),
+            profile_picker: ProfilePicker::new(backend.clone()),
+            error_panel: ErrorPanel::new(),
+            toasts: Toasts::new(),
+            insights: Insights::new(backend.clone()),
+            backend,
+            cursor: Cursor::default(),
+            log_stream: LogStream::default(),
+            log_detail: LogDetail::new(),
+            live_tail_request: LiveTailRequest::default(),
+            time_range: None,
+            next_requests: Vec::new(),
+            stream_cancel_token: None,
+        }
     }
 
-    pub fn start_live_tail(
-        &mut self,
-        log_group_arn_list: Vec<String>,
-        tx: UnboundedSender<Action>,
-    ) {
+    /// Start on the Log Stream, for logs that come from a file or stdin instead of log groups.
+    pub fn focus_log_stream(&mut self) {
+        self.cursor = Cursor::LogStream;
+    }
+
+    pub fn start_live_tail(&mut self, tx: UnboundedSender<Action>) {
         // 既存のlive tailがあれば停止
         self.stop_live_tail();
 
-        if log_group_arn_list.is_empty() {
+        if self.live_tail_request.log_group_identifiers.is_empty() {
+            Self::send_idle_status(&tx);
             return;
         }
 
@@ -111,91 +176,60 @@ impl OuterLayout {
         let cancel_token = CancellationToken::new();
         self.stream_cancel_token = Some(cancel_token.clone());
 
-        tokio::spawn(async move {
-            // Initialize AWS SDK
-            let config = aws_config::load_from_env().await;
-            let client = aws_sdk_cloudwatchlogs::Client::new(&config);
-            let mut stream = client
-                .start_live_tail()
-                .set_log_group_identifiers(Some(log_group_arn_list))
-                .send()
-                .await
-                .unwrap()
-                .response_stream;
-
-            loop {
-                tokio::select! {
-                    // キャンセルシグナルを監視
-                    _ = cancel_token.cancelled() => {
-                        debug!("Live tail cancelled");
-                        break;
-                    }
-                    // ストリームからのデータを処理
-                    result = stream.recv() => {
-                        match result {
-                            Ok(Some(log_event)) => {
-                                if log_event.is_session_start() {
-                                    continue;
-                                }
-
-                                let new_messages = log_event
-                                    .as_session_update()
-                                    .unwrap()
-                                    .session_results
-                                    .as_ref()
-                                    .unwrap()
-                                    .iter()
-                                    .map(|session_result| {
-                                        Message {
-                                            id: Ulid::new(),
-                                            content: session_result.message.as_ref().unwrap().to_string(),
-                                            datetime: DateTime::from_timestamp_millis(
-                                                session_result.timestamp.unwrap(),
-                                            )
-                                            .unwrap()
-                                            .with_timezone(&chrono_tz::Asia::Tokyo),
-                                            url: Message::generate_url(
-                                                session_result.log_group_identifier.as_ref().unwrap(),
-                                                session_result.log_stream_name.as_ref().unwrap(),
-                                            ),
-                                        }
-                                    })
-                                    .collect::<Vec<_>>();
-                                if new_messages.is_empty() {
-                                    // let id = Ulid::new();
-                                    // tx.send(Action::ComponentAction(Box::new(ReceiveNewLog {
-                                    //     new_messages: vec![Message {
-                                    //         id,
-                                    //         url: format!("https://ap-northeast-1.console.aws.amazon.com/cloudwatch/home?region=ap-northeast-1#logsV2:log-groups/log-group/{}"),
-                                    //         content: format!("hoge{}", id),
-                                    //         datetime: Local::now().with_timezone(&Tokyo),
-                                    //     }],
-                                    // }))).unwrap();
-                                    debug!("No new messages in this log event.");
-                                    continue;
-                                }
-
-                                debug!("Received new_messages: {:?}", &new_messages);
-                                if tx.send(Action::ComponentAction(Box::new(ReceiveNewLog {
-                                    new_messages,
-                                }))).is_err() {
-                                    debug!("Failed to send new messages - receiver dropped");
-                                    break;
-                                }
-                            }
-                            Ok(None) => {
-                                debug!("No more log events to process.");
-                                break;
-                            }
-                            Err(e) => {
-                                debug!("Error receiving log events: {:?}", e);
-                                break;
-                            }
-                        }
-                    }
-                }
+        tokio::spawn(live_tail::run(
+            self.backend.clone(),
+            self.live_tail_request.clone(),
+            tx,
+            cancel_token,
+        ));
+    }
+
+    /// Restart live tail, or the historical search when a time range is set.
+    fn restart(&mut self, tx: UnboundedSender<Action>) {
+        match self.time_range.clone() {
+            Some(time_range) => {
+                self.stop_live_tail();
+                Self::send_idle_status(&tx);
+                let requests = history::first_requests(
+                    &self.live_tail_request,
+                    &time_range,
+                    chrono::Utc::now(),
+                );
+                self.search(requests, true, tx);
             }
-        });
+            None => self.start_live_tail(tx),
+        }
+    }
+
+    fn search(
+        &mut self,
+        requests: Vec<FetchEventsRequest>,
+        first_page: bool,
+        tx: UnboundedSender<Action>,
+    ) {
+        self.next_requests.clear();
+        if requests.is_empty() {
+            return;
+        }
+
+        let cancel_token = CancellationToken::new();
+        self.stream_cancel_token = Some(cancel_token.clone());
+
+        let _ = tx.send(Action::ComponentAction(Box::new(SearchEvents {
+            first_page,
+        })));
+        tokio::spawn(history::fetch_page(
+            self.backend.clone(),
+            requests,
+            tx,
+            cancel_token,
+        ));
+    }
+
+    fn send_idle_status(tx: &UnboundedSender<Action>) {
+        let _ = tx.send(Action::ComponentAction(Box::new(ChangeLiveTailStatus {
+            status: LiveTailStatus::Idle,
+        })));
     }
 
     pub fn stop_live_tail(&mut self) {
@@ -215,30 +249,74 @@ impl Component for OuterLayout {
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.log_group_list
+            .register_config_handler(config.clone())?;
+        self.log_stream.register_config_handler(config.clone())?;
+        self.log_detail.register_config_handler(config.clone())?;
+        self.error_panel.register_config_handler(config.clone())?;
+        self.insights.register_config_handler(config)?;
         Ok(())
     }
 
     fn update(&mut self, action: Action, tx: UnboundedSender<Action>) -> Result<()> {
         self.log_group_list.update(action.clone(), tx.clone())?;
+        self.log_stream_list.update(action.clone(), tx.clone())?;
         self.log_stream.update(action.clone(), tx.clone())?;
         self.log_detail.update(action.clone(), tx.clone())?;
+        self.error_panel.update(action.clone(), tx.clone())?;
+        self.toasts.update(action.clone(), tx.clone())?;
+        self.insights.update(action.clone(), tx.clone())?;
 
-        if let Action::ComponentAction(action) = action {
-            if let Some(action) = action
-                .as_any()
-                .downcast_ref::<log_group_list::SelectLogGroup>()
-            {
-                debug!("Log group list updated with {:?} items", &action);
-                self.start_live_tail(
-                    action
-                        .log_groups
-                        .clone()
-                        .into_iter()
-                        .map(|lg| lg.arn)
-                        .collect(),
-                    tx,
-                );
+        let Action::ComponentAction(action) = action else {
+            return Ok(());
+        };
+
+        if action.as_any().downcast_ref::<SelectProfile>().is_some() {
+            // 前のアカウントのロググループのARNは使えないので条件も捨てる
+            self.stop_live_tail();
+            self.live_tail_request = LiveTailRequest::default();
+            self.time_range = None;
+            self.next_requests.clear();
+            Self::send_idle_status(&tx);
+        } else if action
+            .as_any()
+            .downcast_ref::<ReconnectLiveTail>()
+            .is_some()
+        {
+            self.restart(tx);
+        } else if let Some(action) = action.as_any().downcast_ref::<ChangeTimeRange>() {
+            self.time_range = action.time_range.clone();
+            self.restart(tx);
+        } else if action.as_any().downcast_ref::<FetchNextPage>().is_some() {
+            if self.time_range.is_some() {
+                let requests = std::mem::take(&mut self.next_requests);
+                self.search(requests, false, tx);
             }
+        } else if let Some(action) = action.as_any().downcast_ref::<ReceiveEventsPage>() {
+            self.next_requests = action.next_requests.clone();
+        } else if let Some(action) = action
+            .as_any()
+            .downcast_ref::<log_group_list::SelectLogGroup>()
+        {
+            debug!("Log group list updated with {:?} items", &action);
+            self.live_tail_request.log_group_identifiers = action
+                .log_groups
+                .clone()
+                .into_iter()
+                .map(|lg| lg.arn)
+                .collect();
+            // ストリーム指定は選択中のロググループに紐づくのでリセット
+            self.live_tail_request.log_stream_names.clear();
+            self.live_tail_request.log_stream_name_prefixes.clear();
+            self.restart(tx);
+        } else if let Some(action) = action.as_any().downcast_ref::<SelectLogStreams>() {
+            self.live_tail_request.log_stream_names = action.log_stream_names.clone();
+            self.live_tail_request.log_stream_name_prefixes =
+                action.log_stream_name_prefixes.clone();
+            self.restart(tx);
+        } else if let Some(action) = action.as_any().downcast_ref::<ChangeFilterPattern>() {
+            self.live_tail_request.log_event_filter_pattern = action.filter_pattern.clone();
+            self.restart(tx);
         }
         Ok(())
     }
@@ -248,30 +326,89 @@ impl Component for OuterLayout {
         key: crossterm::event::KeyEvent,
         tx: UnboundedSender<Action>,
     ) -> Result<()> {
+        if self.error_panel.is_open() {
+            return self.error_panel.handle_key_event(key, tx);
+        }
+        if self.profile_picker.is_open() {
+            return self.profile_picker.handle_key_event(key, tx);
+        }
+        if self.insights.is_open() {
+            return self.insights.handle_key_event(key, tx);
+        }
+        if self.log_stream.is_editing() {
+            return self.log_stream.handle_key_event(key, tx);
+        }
+        if self.log_stream_list.is_editing() {
+            return self.log_stream_list.handle_key_event(key, tx);
+        }
+        if self.log_detail.is_copying() {
+            return self.log_detail.handle_key_event(key, tx);
+        }
+        if key.code == crossterm::event::KeyCode::Char('P') {
+            return self.profile_picker.open(&tx);
+        }
+        if key.code == crossterm::event::KeyCode::Char('I') {
+            return self.insights.open(&tx);
+        }
+        if key.code == crossterm::event::KeyCode::Char('!') {
+            return self.error_panel.open(&tx);
+        }
+
         match self.cursor {
             Cursor::LogGroupList => {
                 if key.code == crossterm::event::KeyCode::Tab {
-                    self.cursor = Cursor::LogStream;
+                    self.cursor = Cursor::LogStreamList;
                     return Ok(());
                 }
                 self.log_group_list.handle_key_event(key, tx.clone())?;
             }
+            Cursor::LogStreamList => {
+                if key.code == crossterm::event::KeyCode::Tab {
+                    self.cursor = Cursor::LogStream;
+                    return Ok(());
+                }
+                self.log_stream_list.handle_key_event(key, tx)?;
+            }
             Cursor::LogStream => {
                 if key.code == crossterm::event::KeyCode::Tab {
-                    self.cursor = Cursor::LogGroupList;
+                    self.cursor = Cursor::LogDetail;
                     return Ok(());
                 }
 
                 self.log_stream.handle_key_event(key, tx)?;
             }
+            Cursor::LogDetail => {
+                if key.code == crossterm::event::KeyCode::Tab {
+                    self.cursor = Cursor::LogGroupList;
+                    return Ok(());
+                }
+
+                self.log_detail.handle_key_event(key, tx)?;
+            }
         }
         Ok(())
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if self.insights.is_open() {
+            let [insights_area, log_detail_area] =
+                Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                    .areas(area);
+            self.insights.draw(frame, insights_area)?;
+            self.log_detail.draw(frame, log_detail_area, false)?;
+            self.profile_picker.draw(frame, area)?;
+            self.toasts.draw(frame, area)?;
+            self.error_panel.draw(frame, area)?;
+            return Ok(());
+        }
+
         let constraints = match self.cursor {
-            Cursor::LogGroupList => vec![Constraint::Percentage(70), Constraint::Percentage(30)],
-            Cursor::LogStream => vec![Constraint::Percentage(50), Constraint::Percentage(50)],
+            Cursor::LogGroupList | Cursor::LogStreamList => {
+                vec![Constraint::Percentage(70), Constraint::Percentage(30)]
+            }
+            Cursor::LogStream | Cursor::LogDetail => {
+                vec![Constraint::Percentage(50), Constraint::Percentage(50)]
+            }
         };
 
         let outer_layout = Layout::default()
@@ -280,15 +417,310 @@ impl Component for OuterLayout {
             .split(area);
 
         match self.cursor {
-            Cursor::LogGroupList => {
-                self.log_group_list.draw(frame, outer_layout[0])?;
+            Cursor::LogGroupList | Cursor::LogStreamList => {
+                let [log_group_area, log_stream_area] =
+                    Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)])
+                        .areas(outer_layout[0]);
+                self.log_group_list.draw(frame, log_group_area)?;
+                self.log_stream_list.draw(frame, log_stream_area)?;
             }
-            Cursor::LogStream => {
-                self.log_detail.draw(frame, outer_layout[0])?;
+            Cursor::LogStream | Cursor::LogDetail => {
+                let focused = matches!(self.cursor, Cursor::LogDetail);
+                self.log_detail.draw(frame, outer_layout[0], focused)?;
             }
         }
 
         self.log_stream.draw(frame, outer_layout[1])?;
+        self.profile_picker.draw(frame, area)?;
+        self.toasts.draw(frame, area)?;
+        self.error_panel.draw(frame, area)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        backend::{EventsPage, fake::FakeLogBackend},
+        components::{log_group_list::LogGroup, log_stream::SelectLog},
+    };
+
+    #[test]
+    fn test_generate_url() {
+        let url = Message::generate_url(
+            "us-east-1",
+            "123456789012:/aws/lambda/foo",
+            "2025/06/07/[$LATEST]abc",
+        );
+        assert!(url.starts_with("https://us-east-1.console.aws.amazon.com/"));
+        assert!(url.contains("?region=us-east-1#"));
+        assert!(url.contains("log-group/%252Faws%252Flambda%252Ffoo/"));
+
+        let url = Message::generate_url(
+            "eu-west-1",
+            "arn:aws:logs:ap-northeast-1:123456789012:log-group:/aws/lambda/foo:*",
+            "stream",
+        );
+        assert!(url.contains("log-group/%252Faws%252Flambda%252Ffoo/log-events/stream"));
+    }
+
+    #[tokio::test]
+    async fn test_select_log_group_starts_live_tail() {
+        let batches = vec![vec![message("first")], vec![], vec![message("second")]];
+        let backend = Arc::new(FakeLogBackend::new().live_tail_batches(batches));
+        let mut outer_layout = OuterLayout::new(backend.clone());
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let select_action = log_group_list::SelectLogGroup {
+            log_groups: vec![LogGroup {
+                arn: "arn:aws:logs:ap-northeast-1:123456789012:log-group:foo".to_string(),
+                ..Default::default()
+            }],
+        };
+        outer_layout
+            .update(Action::ComponentAction(Box::new(select_action)), tx)
+            .unwrap();
+
+        let received = receive_contents(&mut rx, 2).await;
+        assert_eq!(received, vec!["first", "second"]);
+        assert_eq!(
+            backend.live_tail_requests()[0],
+            LiveTailRequest {
+                log_group_identifiers: vec![
+                    "arn:aws:logs:ap-northeast-1:123456789012:log-group:foo".to_string()
+                ],
+                log_event_filter_pattern: None,
+                ..Default::default()
+            }
+        );
+        outer_layout.stop_live_tail();
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_skips_overlap() {
+        let first = message("first");
+        let sessions = vec![
+            vec![vec![first.clone()]],
+            // 再接続後のセッションは直前のイベントを再送することがある
+            vec![vec![first, message("second")]],
+        ];
+        let backend = Arc::new(FakeLogBackend::new().live_tail_sessions(sessions));
+        let mut outer_layout = OuterLayout::new(backend.clone());
+        outer_layout.live_tail_request.log_group_identifiers = vec!["foo".to_string()];
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        outer_layout.start_live_tail(tx);
+
+        let received = receive_contents(&mut rx, 2).await;
+        assert_eq!(received, vec!["first", "second"]);
+        assert_eq!(backend.live_tail_requests().len(), 3);
+        outer_layout.stop_live_tail();
+    }
+
+    #[test]
+    fn test_copy_menu_takes_keys() {
+        let mut outer_layout = OuterLayout::new(Arc::new(FakeLogBackend::new()));
+        outer_layout.cursor = Cursor::LogDetail;
+        let (tx, _rx) = mpsc::unbounded_channel();
+        outer_layout
+            .update(
+                Action::ComponentAction(Box::new(SelectLog {
+                    selected_log: message(r#"{"level":"INFO"}"#),
+                })),
+                tx.clone(),
+            )
+            .unwrap();
+        let press = |outer_layout: &mut OuterLayout, code: crossterm::event::KeyCode| {
+            outer_layout
+                .handle_key_event(code.into(), tx.clone())
+                .unwrap();
+        };
+
+        press(&mut outer_layout, crossterm::event::KeyCode::Char('c'));
+        assert!(outer_layout.log_detail.is_copying());
+        // メニューを開いている間は他のパネルを開かず、カーソルも移動しない
+        press(&mut outer_layout, crossterm::event::KeyCode::Char('P'));
+        press(&mut outer_layout, crossterm::event::KeyCode::Char('!'));
+        press(&mut outer_layout, crossterm::event::KeyCode::Tab);
+        assert!(!outer_layout.profile_picker.is_open());
+        assert!(!outer_layout.error_panel.is_open());
+        assert!(matches!(outer_layout.cursor, Cursor::LogDetail));
+
+        press(&mut outer_layout, crossterm::event::KeyCode::Esc);
+        assert!(!outer_layout.log_detail.is_copying());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_key_restarts_live_tail() {
+        let backend = Arc::new(FakeLogBackend::new());
+        let mut outer_layout = OuterLayout::new(backend.clone());
+        outer_layout.live_tail_request.log_group_identifiers = vec!["foo".to_string()];
+        outer_layout.cursor = Cursor::LogStream;
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        outer_layout
+            .handle_key_event(crossterm::event::KeyCode::Char('r').into(), tx.clone())
+            .unwrap();
+        let Some(action) = rx.recv().await else {
+            panic!("expected ReconnectLiveTail");
+        };
+        outer_layout.update(action, tx).unwrap();
+
+        tokio::task::yield_now().await;
+        assert_eq!(backend.live_tail_requests().len(), 1);
+        outer_layout.stop_live_tail();
+    }
+
+    #[tokio::test]
+    async fn test_time_range_pages_through_events() {
+        let backend = Arc::new(FakeLogBackend::new().events_pages(vec![
+            EventsPage {
+                messages: vec![message("first")],
+                next_token: Some("token".to_string()),
+            },
+            EventsPage {
+                messages: vec![message("second")],
+                next_token: None,
+            },
+        ]));
+        let mut outer_layout = OuterLayout::new(backend.clone());
+        outer_layout.live_tail_request.log_group_identifiers = vec!["foo".to_string()];
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let change_time_range = ChangeTimeRange {
+            time_range: Some(TimeRange::parse("-15m", chrono_tz::Tz::UTC).unwrap()),
+        };
+        outer_layout
+            .update(
+                Action::ComponentAction(Box::new(change_time_range)),
+                tx.clone(),
+            )
+            .unwrap();
+        assert_eq!(
+            receive_page(&mut outer_layout, &mut rx).await,
+            vec!["first"]
+        );
+        assert!(backend.live_tail_requests().is_empty());
+
+        outer_layout
+            .update(Action::ComponentAction(Box::new(FetchNextPage)), tx)
+            .unwrap();
+        assert_eq!(
+            receive_page(&mut outer_layout, &mut rx).await,
+            vec!["second"]
+        );
+        assert!(outer_layout.next_requests.is_empty());
+
+        let requests = backend.fetch_events_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].next_token, Some("token".to_string()));
+    }
+
+    /// Feed actions back to the layout until a page of search results arrived.
+    async fn receive_page(
+        outer_layout: &mut OuterLayout,
+        rx: &mut mpsc::UnboundedReceiver<Action>,
+    ) -> Vec<String> {
+        loop {
+            let Some(action) = rx.recv().await else {
+                panic!("expected ReceiveEventsPage");
+            };
+            let page = match &action {
+                Action::ComponentAction(component_action) => component_action
+                    .as_any()
+                    .downcast_ref::<ReceiveEventsPage>()
+                    .cloned(),
+                _ => None,
+            };
+            let (tx, _rx) = mpsc::unbounded_channel();
+            outer_layout.update(action, tx).unwrap();
+            if let Some(page) = page {
+                return page.messages.into_iter().map(|m| m.content).collect();
+            }
+        }
+    }
+
+    /// Collect the contents of received logs until `count` messages arrived.
+    async fn receive_contents(
+        rx: &mut mpsc::UnboundedReceiver<Action>,
+        count: usize,
+    ) -> Vec<String> {
+        let mut received = Vec::new();
+        while received.len() < count {
+            let Some(Action::ComponentAction(action)) = rx.recv().await else {
+                continue;
+            };
+            if let Some(batch) = action.as_any().downcast_ref::<ReceiveNewLog>() {
+                received.extend(batch.new_messages.iter().map(|m| m.content.clone()));
+            }
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_change_filter_pattern_restarts_live_tail() {
+        let backend = Arc::new(FakeLogBackend::new());
+        let mut outer_layout = OuterLayout::new(backend.clone());
+        outer_layout.live_tail_request.log_group_identifiers = vec!["foo".to_string()];
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let change_filter_pattern = ChangeFilterPattern {
+            filter_pattern: Some("{ $.level = \"ERROR\" }".to_string()),
+        };
+        outer_layout
+            .update(Action::ComponentAction(Box::new(change_filter_pattern)), tx)
+            .unwrap();
+
+        // live tailはspawnされたタスクで開始される
+        tokio::task::yield_now().await;
+        assert_eq!(
+            backend.live_tail_requests(),
+            vec![LiveTailRequest {
+                log_group_identifiers: vec!["foo".to_string()],
+                log_event_filter_pattern: Some("{ $.level = \"ERROR\" }".to_string()),
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_profile_forgets_log_groups() {
+        let backend = Arc::new(FakeLogBackend::new());
+        let mut outer_layout = OuterLayout::new(backend.clone());
+        outer_layout.live_tail_request = LiveTailRequest {
+            log_group_identifiers: vec![
+                "arn:aws:logs:ap-northeast-1:111111111111:log-group:foo".to_string(),
+            ],
+            log_event_filter_pattern: Some("ERROR".to_string()),
+            ..Default::default()
+        };
+        outer_layout.cursor = Cursor::LogStream;
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let select_profile = SelectProfile {
+            profile: "other-account".to_string(),
+        };
+        outer_layout
+            .update(
+                Action::ComponentAction(Box::new(select_profile)),
+                tx.clone(),
+            )
+            .unwrap();
+        assert_eq!(outer_layout.live_tail_request, LiveTailRequest::default());
+        assert_eq!(outer_layout.time_range, None);
+
+        // 再接続しても前のアカウントのロググループには接続しない
+        outer_layout
+            .handle_key_event(crossterm::event::KeyCode::Char('r').into(), tx.clone())
+            .unwrap();
+        while let Ok(action) = rx.try_recv() {
+            outer_layout.update(action, tx.clone()).unwrap();
+        }
+        tokio::task::yield_now().await;
+        assert!(backend.live_tail_requests().is_empty());
+        assert!(backend.fetch_events_requests().is_empty());
+    }
+}
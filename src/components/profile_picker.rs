@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::debug;
+
+use super::Component;
+use crate::{
+    action::{Action, ComponentAction},
+    app::Mode,
+    backend::LogBackend,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelectProfile {
+    pub profile: String,
+}
+impl ComponentAction for SelectProfile {
+    fn name(&self) -> &'static str {
+        "SelectProfile"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Popup listing the profiles from the shared AWS config and credentials files.
+#[derive(Clone, Debug)]
+pub struct ProfilePicker {
+    backend: Arc<dyn LogBackend>,
+    is_open: bool,
+    profiles: Vec<String>,
+    list_state: ListState,
+}
+
+impl ProfilePicker {
+    pub fn new(backend: Arc<dyn LogBackend>) -> Self {
+        Self {
+            backend,
+            is_open: false,
+            profiles: Vec::new(),
+            list_state: ListState::default(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Single key bindings such as `q` are disabled while open.
+    pub fn open(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        self.profiles = self.backend.list_profiles().unwrap_or_else(|e| {
+            debug!("Failed to list profiles: {}", e);
+            Vec::new()
+        });
+        // 現在のプロファイルにカーソルを合わせる
+        let current = self.backend.profile();
+        let selected = self
+            .profiles
+            .iter()
+            .position(|profile| Some(profile) == current.as_ref())
+            .unwrap_or(0);
+        self.list_state.select(Some(selected));
+        self.is_open = true;
+        tx.send(Action::ChangeMode(Mode::Input))?;
+        Ok(())
+    }
+
+    pub fn close(&mut self, tx: &UnboundedSender<Action>) -> Result<()> {
+        self.is_open = false;
+        tx.send(Action::ChangeMode(Mode::Home))?;
+        Ok(())
+    }
+}
+
+impl Component for ProfilePicker {
+    fn handle_key_event(
+        &mut self,
+        key: crossterm::event::KeyEvent,
+        tx: UnboundedSender<Action>,
+    ) -> Result<()> {
+        match key.code {
+            crossterm::event::KeyCode::Up => {
+                self.list_state.select_previous();
+            }
+            crossterm::event::KeyCode::Down => {
+                self.list_state.select_next();
+            }
+            crossterm::event::KeyCode::Esc => {
+                self.close(&tx)?;
+            }
+            crossterm::event::KeyCode::Enter => {
+                if let Some(profile) = self
+                    .list_state
+                    .selected()
+                    .and_then(|index| self.profiles.get(index))
+                {
+                    debug!("Switching to profile {}", profile);
+                    self.backend.set_profile(profile.clone());
+                    tx.send(Action::ComponentAction(Box::new(SelectProfile {
+                        profile: profile.clone(),
+                    })))?;
+                }
+                self.close(&tx)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.is_open {
+            return Ok(());
+        }
+
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(40),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(self.profiles.len().clamp(1, 15) as u16 + 2),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        let current = self.backend.profile();
+        let items = self.profiles.iter().map(|profile| {
+            if Some(profile) == current.as_ref() {
+                ListItem::new(format!("{profile} *")).style(Style::new().fg(Color::Yellow))
+            } else {
+                ListItem::new(profile.as_str())
+            }
+        });
+        let title = if self.profiles.is_empty() {
+            "AWS Profile (none found)"
+        } else {
+            "AWS Profile"
+        };
+
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::new().reversed())
+                .highlight_symbol(">")
+                .block(Block::bordered().title(title)),
+            area,
+            &mut self.list_state,
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::backend::fake::FakeLogBackend;
+
+    #[test]
+    fn test_select_profile() {
+        let backend = Arc::new(
+            FakeLogBackend::new().profiles(vec!["default".to_string(), "prod".to_string()]),
+        );
+        let mut profile_picker = ProfilePicker::new(backend.clone());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        // 開いている間は`q`などのキーバインドを無効にする
+        profile_picker.open(&tx).unwrap();
+        assert_eq!(rx.try_recv().unwrap(), Action::ChangeMode(Mode::Input));
+        profile_picker
+            .handle_key_event(crossterm::event::KeyCode::Down.into(), tx.clone())
+            .unwrap();
+        profile_picker
+            .handle_key_event(crossterm::event::KeyCode::Enter.into(), tx)
+            .unwrap();
+
+        assert!(!profile_picker.is_open());
+        assert_eq!(backend.profile(), Some("prod".to_string()));
+        let Ok(Action::ComponentAction(action)) = rx.try_recv() else {
+            panic!("expected SelectProfile");
+        };
+        assert_eq!(
+            action.as_any().downcast_ref::<SelectProfile>(),
+            Some(&SelectProfile {
+                profile: "prod".to_string()
+            })
+        );
+        assert_eq!(rx.try_recv().unwrap(), Action::ChangeMode(Mode::Home));
+    }
+}
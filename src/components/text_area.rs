@@ -0,0 +1,164 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Paragraph},
+};
+
+/// Multi-line text editor used by the Logs Insights query editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextArea {
+    lines: Vec<String>,
+    row: usize,
+    /// cursor position in the row counted in chars
+    col: usize,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl TextArea {
+    pub fn new(value: &str) -> Self {
+        let lines: Vec<String> = value.split('\n').map(str::to_string).collect();
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Self { lines, row, col }
+    }
+
+    pub fn value(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    fn byte_index(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map_or(line.len(), |(index, _)| index)
+    }
+
+    /// Edit the text. Keys with Ctrl or Alt are left to the owner.
+    pub fn handle_key_event(&mut self, key: KeyEvent) {
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char(c) => {
+                let index = self.byte_index();
+                self.lines[self.row].insert(index, c);
+                self.col += 1;
+            }
+            KeyCode::Enter => {
+                let index = self.byte_index();
+                let rest = self.lines[self.row].split_off(index);
+                self.row += 1;
+                self.lines.insert(self.row, rest);
+                self.col = 0;
+            }
+            KeyCode::Backspace if self.col > 0 => {
+                self.col -= 1;
+                let index = self.byte_index();
+                self.lines[self.row].remove(index);
+            }
+            KeyCode::Backspace if self.row > 0 => {
+                // 行頭では前の行と結合する
+                let line = self.lines.remove(self.row);
+                self.row -= 1;
+                self.col = self.line_len(self.row);
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Delete if self.col < self.line_len(self.row) => {
+                let index = self.byte_index();
+                self.lines[self.row].remove(index);
+            }
+            KeyCode::Delete if self.row + 1 < self.lines.len() => {
+                let line = self.lines.remove(self.row + 1);
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Left if self.col > 0 => self.col -= 1,
+            KeyCode::Left if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.line_len(self.row);
+            }
+            KeyCode::Right if self.col < self.line_len(self.row) => self.col += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(self.row),
+            _ => {}
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect, block: Block, focused: bool) {
+        let inner = block.inner(area);
+        // カーソル行が見えるようにスクロールする
+        let scroll = (self.row as u16).saturating_sub(inner.height.saturating_sub(1));
+        frame.render_widget(
+            Paragraph::new(
+                self.lines
+                    .iter()
+                    .map(|line| Line::from(line.as_str()))
+                    .collect::<Vec<_>>(),
+            )
+            .scroll((scroll, 0))
+            .block(block),
+            area,
+        );
+
+        if focused {
+            let prefix: String = self.lines[self.row].chars().take(self.col).collect();
+            frame.set_cursor_position(Position::new(
+                inner.x + Line::from(prefix).width() as u16,
+                inner.y + self.row as u16 - scroll,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_edit_lines() {
+        let mut text_area = TextArea::new("fields @message");
+        text_area.handle_key_event(KeyCode::Enter.into());
+        for c in "| limit 5".chars() {
+            text_area.handle_key_event(KeyCode::Char(c).into());
+        }
+        assert_eq!(text_area.value(), "fields @message\n| limit 5");
+
+        text_area.handle_key_event(KeyCode::Home.into());
+        text_area.handle_key_event(KeyCode::Backspace.into());
+        assert_eq!(text_area.value(), "fields @message| limit 5");
+
+        text_area.handle_key_event(KeyCode::Enter.into());
+        text_area.handle_key_event(KeyCode::Up.into());
+        text_area.handle_key_event(KeyCode::End.into());
+        text_area.handle_key_event(KeyCode::Delete.into());
+        assert_eq!(text_area.value(), "fields @message| limit 5");
+
+        // Ctrl付きのキーは入力しない
+        text_area.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert_eq!(text_area.value(), "fields @message| limit 5");
+    }
+}
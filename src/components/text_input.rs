@@ -0,0 +1,120 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Paragraph},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputOutcome {
+    /// The input is still being edited.
+    Pending,
+    /// Enter was pressed with the given value.
+    Submit(String),
+    /// Esc was pressed.
+    Cancel,
+}
+
+/// Single line text input used by the prompts in the panes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    /// cursor position counted in chars
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new(value: &str) -> Self {
+        Self {
+            value: value.to_string(),
+            cursor: value.chars().count(),
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .nth(self.cursor)
+            .map_or(self.value.len(), |(index, _)| index)
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> InputOutcome {
+        match key.code {
+            KeyCode::Enter => return InputOutcome::Submit(self.value.clone()),
+            KeyCode::Esc => return InputOutcome::Cancel,
+            KeyCode::Char(c) => {
+                let index = self.byte_index();
+                self.value.insert(index, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                let index = self.byte_index();
+                self.value.remove(index);
+            }
+            KeyCode::Delete if self.cursor < self.value.chars().count() => {
+                let index = self.byte_index();
+                self.value.remove(index);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.value.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.chars().count(),
+            _ => {}
+        }
+        InputOutcome::Pending
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect, title: &str) {
+        frame.render_widget(
+            Paragraph::new(self.value.as_str()).block(Block::bordered().title(title.to_string())),
+            area,
+        );
+        let prefix: String = self.value.chars().take(self.cursor).collect();
+        frame.set_cursor_position(Position::new(
+            area.x + 1 + Line::from(prefix).width() as u16,
+            area.y + 1,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_edit_and_submit() {
+        let mut input = TextInput::new("ERRO");
+        assert_eq!(
+            input.handle_key_event(KeyCode::Char('R').into()),
+            InputOutcome::Pending
+        );
+        input.handle_key_event(KeyCode::Home.into());
+        input.handle_key_event(KeyCode::Char('?').into());
+        input.handle_key_event(KeyCode::End.into());
+        input.handle_key_event(KeyCode::Left.into());
+        input.handle_key_event(KeyCode::Backspace.into());
+        assert_eq!(input.value, "?ERRR");
+        input.handle_key_event(KeyCode::Delete.into());
+        assert_eq!(
+            input.handle_key_event(KeyCode::Enter.into()),
+            InputOutcome::Submit("?ERR".to_string())
+        );
+        assert_eq!(
+            input.handle_key_event(KeyCode::Esc.into()),
+            InputOutcome::Cancel
+        );
+    }
+
+    #[test]
+    fn test_multibyte() {
+        let mut input = TextInput::new("エラー");
+        input.handle_key_event(KeyCode::Backspace.into());
+        input.handle_key_event(KeyCode::Left.into());
+        input.handle_key_event(KeyCode::Char('ん').into());
+        assert_eq!(input.value, "エんラ");
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::Result;
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::Component;
+use crate::action::{Action, ComponentAction};
+
+/// How long a notification stays visible.
+const TOAST_DURATION_SECS: i64 = 4;
+/// Toasts shown at the same time, newest first.
+const MAX_TOASTS: usize = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShowToast {
+    pub title: String,
+    pub body: String,
+}
+impl ComponentAction for ShowToast {
+    fn name(&self) -> &'static str {
+        "ShowToast"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Toast {
+    pub datetime: DateTime<Utc>,
+    pub title: String,
+    pub body: String,
+}
+
+/// Where a `ToastStack` is drawn. New toasts are placed nearest to the corner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corner {
+    TopRight,
+    BottomRight,
+}
+
+/// Toasts stacked in a corner, each visible for a while after it is pushed.
+#[derive(Clone, Debug)]
+pub struct ToastStack {
+    toasts: Vec<Toast>,
+    duration: Duration,
+    border_style: Style,
+    corner: Corner,
+}
+
+impl ToastStack {
+    pub fn new(duration: Duration, border_style: Style, corner: Corner) -> Self {
+        Self {
+            toasts: Vec::new(),
+            duration,
+            border_style,
+            corner,
+        }
+    }
+
+    pub fn push(&mut self, title: String, body: String) {
+        let now = Utc::now();
+        // 表示が終わったものは捨てる
+        self.toasts
+            .retain(|toast| now - toast.datetime < self.duration);
+        self.toasts.push(Toast {
+            datetime: now,
+            title,
+            body,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+
+    pub fn recent(&self, now: DateTime<Utc>) -> Vec<&Toast> {
+        self.toasts
+            .iter()
+            .rev()
+            .take_while(|toast| now - toast.datetime < self.duration)
+            .take(MAX_TOASTS)
+            .collect()
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect) {
+        let width = area.width.min(60);
+        // 角から順に積み上げる
+        let mut remaining = area.height;
+        for toast in self.recent(Utc::now()) {
+            let height = (toast.body.chars().count() as u16 / width.saturating_sub(2).max(1) + 3)
+                .min(6)
+                .min(remaining);
+            if height < 3 {
+                break;
+            }
+            let offset = area.height - remaining;
+            let y = match self.corner {
+                Corner::TopRight => area.y + offset,
+                Corner::BottomRight => area.bottom() - offset - height,
+            };
+            remaining -= height;
+            let toast_area = Rect::new(area.right() - width, y, width, height);
+
+            frame.render_widget(Clear, toast_area);
+            frame.render_widget(
+                Paragraph::new(toast.body.as_str())
+                    .wrap(Wrap { trim: true })
+                    .block(
+                        Block::bordered()
+                            .border_style(self.border_style)
+                            .title(toast.title.as_str()),
+                    ),
+                toast_area,
+            );
+        }
+    }
+}
+
+/// Shows notifications in the top right corner for a few seconds.
+#[derive(Clone, Debug)]
+pub struct Toasts {
+    stack: ToastStack,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self {
+            stack: ToastStack::new(
+                Duration::seconds(TOAST_DURATION_SECS),
+                Style::new().fg(Color::Green),
+                Corner::TopRight,
+            ),
+        }
+    }
+}
+
+impl Component for Toasts {
+    fn update(&mut self, action: Action, _tx: UnboundedSender<Action>) -> Result<()> {
+        let Action::ComponentAction(action) = action else {
+            return Ok(());
+        };
+        if let Some(action) = action.as_any().downcast_ref::<ShowToast>() {
+            self.stack.push(action.title.clone(), action.body.clone());
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        self.stack.draw(frame, area);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn test_show_toasts() {
+        let mut toasts = Toasts::new();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        for title in ["Copy", "Log Export"] {
+            toasts
+                .update(
+                    Action::ComponentAction(Box::new(ShowToast {
+                        title: title.to_string(),
+                        body: String::new(),
+                    })),
+                    tx.clone(),
+                )
+                .unwrap();
+        }
+        toasts.update(Action::Tick, tx).unwrap();
+
+        let now = Utc::now();
+        let recent: Vec<_> = toasts
+            .stack
+            .recent(now)
+            .iter()
+            .map(|toast| toast.title.as_str())
+            .collect();
+        assert_eq!(recent, vec!["Log Export", "Copy"]);
+        assert!(
+            toasts
+                .stack
+                .recent(now + Duration::seconds(TOAST_DURATION_SECS))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_draw_toast_stack() {
+        let mut stack = ToastStack::new(
+            Duration::seconds(TOAST_DURATION_SECS),
+            Style::new(),
+            Corner::BottomRight,
+        );
+        stack.push("Error".to_string(), "first".to_string());
+        stack.push("Error".to_string(), "second".to_string());
+
+        let mut terminal = Terminal::new(backend::TestBackend::new(20, 8)).unwrap();
+        terminal
+            .draw(|frame| stack.draw(frame, frame.area()))
+            .unwrap();
+        let rows: Vec<String> = (0..8)
+            .map(|y| {
+                (0..20)
+                    .map(|x| terminal.backend().buffer()[(x, y)].symbol())
+                    .collect()
+            })
+            .collect();
+        // 新しいものほど角に近い
+        assert!(rows[3].contains("first"));
+        assert!(rows[6].contains("second"));
+        assert_eq!(rows[0].trim(), "");
+    }
+}
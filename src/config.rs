@@ -2,25 +2,187 @@
 
 use std::{collections::HashMap, env, path::PathBuf};
 
+use chrono_tz::Tz;
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use derive_deref::{Deref, DerefMut};
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::{
+    layout::Alignment,
+    style::{Color, Modifier, Style},
+};
 use serde::{Deserialize, de::Deserializer};
+use serde_json::Value;
 use tracing::error;
 
-use crate::{action::Action, app::Mode};
+use crate::{
+    action::Action, app::Mode, date::parse_timezone, json_path::JsonPath,
+    notification::NotificationBackend,
+};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
-#[derive(Clone, Debug, Deserialize, Default)]
+/// Number of logs the Log Stream keeps in memory unless configured.
+pub const DEFAULT_SCROLLBACK: usize = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
 pub struct AppConfig {
     #[serde(default)]
     pub data_dir: PathBuf,
     #[serde(default)]
     pub config_dir: PathBuf,
+    /// AWS region used for API calls and console URLs. Falls back to the SDK's resolution.
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Timezone used to display timestamps and name exported files.
+    #[serde(
+        default = "default_timezone",
+        deserialize_with = "deserialize_timezone"
+    )]
+    pub timezone: Tz,
+    /// Number of logs the Log Stream keeps in memory. Older logs are moved to the data dir.
+    #[serde(default = "default_scrollback")]
+    pub scrollback: usize,
+    /// Extra columns of the Log Stream for every log group.
+    #[serde(default)]
+    pub columns: Vec<ColumnConfig>,
+    /// Show only the `message` field of JSON logs in the log column.
+    #[serde(default)]
+    pub message_only: bool,
+    /// Columns for specific log groups, used in addition to `columns`.
+    #[serde(default)]
+    pub log_groups: Vec<LogGroupConfig>,
+    /// `auto`, `macos`, `freedesktop`, `bell` or `toast`.
+    #[serde(default)]
+    pub notification: NotificationBackend,
+    /// Command reading the text to copy from stdin, e.g. `["wl-copy"]`. Tried before the system
+    /// clipboard and OSC 52.
+    #[serde(default)]
+    pub clipboard_command: Option<Vec<String>>,
+    /// Directory suggested for exports. Defaults to `exports` in the data dir.
+    #[serde(default)]
+    pub export_dir: Option<PathBuf>,
+    /// Name of exported files with `{datetime}`, `{date}`, `{time}`, `{log_group}`, `{count}` and
+    /// `{ext}` placeholders.
+    #[serde(default = "default_export_filename")]
+    pub export_filename: String,
+}
+
+/// Column of the Log Stream showing a field of JSON logs.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct ColumnConfig {
+    /// e.g. `$.requestId` or `$.http.status`
+    #[serde(deserialize_with = "deserialize_json_path")]
+    pub path: JsonPath,
+    /// Header of the column. Defaults to the last key of the path.
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default = "default_column_width")]
+    pub width: u16,
+    #[serde(default)]
+    pub align: ColumnAlign,
+}
+
+impl ColumnConfig {
+    pub fn header(&self) -> String {
+        self.title
+            .clone()
+            .unwrap_or_else(|| self.path.last_key().to_string())
+    }
+
+    /// Text of the field in a parsed JSON log. Empty if the log has no such field.
+    pub fn value(&self, json: Option<&Value>) -> String {
+        match json.and_then(|json| self.path.get(json)) {
+            Some(Value::String(text)) => text.clone(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl From<ColumnAlign> for Alignment {
+    fn from(align: ColumnAlign) -> Self {
+        match align {
+            ColumnAlign::Left => Alignment::Left,
+            ColumnAlign::Center => Alignment::Center,
+            ColumnAlign::Right => Alignment::Right,
+        }
+    }
+}
+
+/// Settings of the Log Stream while the log group is selected.
+///
+/// A list instead of a map keyed by name, since keys are lowercased when the config is loaded.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct LogGroupConfig {
+    /// Name of the log group, e.g. `/aws/lambda/my-function`
+    pub log_group: String,
+    #[serde(default)]
+    pub columns: Vec<ColumnConfig>,
+    /// Overrides `message_only` of the global config.
+    #[serde(default)]
+    pub message_only: Option<bool>,
+}
+
+fn default_column_width() -> u16 {
+    12
+}
+
+fn deserialize_json_path<'de, D>(deserializer: D) -> Result<JsonPath, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let path = String::deserialize(deserializer)?;
+    JsonPath::parse(&path).map_err(serde::de::Error::custom)
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            data_dir: PathBuf::default(),
+            config_dir: PathBuf::default(),
+            region: None,
+            timezone: default_timezone(),
+            scrollback: DEFAULT_SCROLLBACK,
+            columns: Vec::new(),
+            message_only: false,
+            log_groups: Vec::new(),
+            notification: NotificationBackend::default(),
+            clipboard_command: None,
+            export_dir: None,
+            export_filename: default_export_filename(),
+        }
+    }
+}
+
+fn default_export_filename() -> String {
+    "saved_logs_{datetime}.{ext}".to_string()
+}
+
+fn default_scrollback() -> usize {
+    DEFAULT_SCROLLBACK
+}
+
+fn default_timezone() -> Tz {
+    parse_timezone("local").unwrap_or_default()
+}
+
+fn deserialize_timezone<'de, D>(deserializer: D) -> Result<Tz, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    parse_timezone(&name).map_err(serde::de::Error::custom)
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -509,9 +671,61 @@ mod tests {
                 .unwrap(),
             &Action::Quit
         );
+        // 設定ファイルの有無で既定値が変わらない
+        assert_eq!(c.config.timezone, AppConfig::default().timezone);
         Ok(())
     }
 
+    #[test]
+    fn test_default_level_styles() -> Result<()> {
+        let c = Config::new()?;
+        let styles = c.styles.get(&Mode::Home).unwrap();
+        assert_eq!(
+            styles.get("level.error").unwrap(),
+            &Style::new()
+                .fg(Color::Indexed(1))
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(
+            styles.get("level.warn").unwrap().fg,
+            Some(Color::Indexed(3))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_columns() {
+        let config: AppConfig = json5::from_str(
+            r#"{
+                columns: [{ path: "$.requestId", width: 26 }],
+                log_groups: [{
+                    log_group: "/aws/lambda/Api",
+                    columns: [{ path: "$.http.status", title: "Status", align: "right" }],
+                    message_only: true,
+                }],
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.columns[0].path,
+            JsonPath::parse("requestId").unwrap()
+        );
+        assert_eq!(config.columns[0].width, 26);
+        assert_eq!(config.columns[0].align, ColumnAlign::Left);
+        assert_eq!(
+            config.log_groups[0].columns[0],
+            ColumnConfig {
+                path: JsonPath::parse("$.http.status").unwrap(),
+                title: Some("Status".to_string()),
+                width: 12,
+                align: ColumnAlign::Right,
+            }
+        );
+        assert_eq!(config.log_groups[0].message_only, Some(true));
+
+        assert!(json5::from_str::<AppConfig>(r#"{ columns: [{ path: "$..a" }] }"#).is_err());
+    }
+
     #[test]
     fn test_simple_keys() {
         assert_eq!(
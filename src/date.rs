@@ -1,11 +1,11 @@
-use chrono::{DateTime, Utc};
-use chrono_tz::{Asia::Tokyo, Tz};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 
 ///
 /// get the difference between the current time and the given date
 /// ex. 50s, 5m, 2h, 1d, 10M, 2y, 15y,
-pub fn get_diff(date: DateTime<Tz>) -> String {
-    let now = Utc::now().with_timezone(&Tokyo);
+pub fn get_diff(date: DateTime<Utc>) -> String {
+    let now = Utc::now();
     let duration = now - date;
     let seconds = duration.num_seconds();
 
@@ -26,13 +26,141 @@ pub fn get_diff(date: DateTime<Tz>) -> String {
     format!("{value}{unit}")
 }
 
+///
+/// parse a display timezone
+/// ex. Asia/Tokyo, America/New_York, utc, local
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "utc" => Ok(Tz::UTC),
+        "local" => Ok(local_timezone()),
+        _ => name
+            .parse::<Tz>()
+            .map_err(|_| format!("Unknown timezone `{name}`")),
+    }
+}
+
+fn local_timezone() -> Tz {
+    // TZ環境変数・OS設定から取得できない場合はUTCにフォールバック
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse::<Tz>().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// One end of a `TimeRange`, either relative to now or a fixed point in time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimeBound {
+    Ago(Duration),
+    At(DateTime<Utc>),
+}
+
+impl TimeBound {
+    fn parse(input: &str, timezone: Tz) -> Result<Self, String> {
+        if let Some(duration) = parse_relative(input) {
+            return Ok(TimeBound::Ago(duration));
+        }
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+            return Ok(TimeBound::At(datetime.with_timezone(&Utc)));
+        }
+
+        // オフセットの無い日時は表示用のタイムゾーンで解釈する
+        let naive = [
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%d %H:%M",
+            "%Y-%m-%dT%H:%M:%S",
+            "%Y-%m-%dT%H:%M",
+        ]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| format!("Unknown time `{input}`"))?;
+        timezone
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|datetime| TimeBound::At(datetime.with_timezone(&Utc)))
+            .ok_or_else(|| format!("`{input}` does not exist in {timezone}"))
+    }
+
+    pub fn resolve(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            TimeBound::Ago(duration) => now - *duration,
+            TimeBound::At(datetime) => *datetime,
+        }
+    }
+}
+
+/// ex. 15m, -15m, 2h, 7d, 1w
+fn parse_relative(input: &str) -> Option<Duration> {
+    let input = input.strip_prefix('-').unwrap_or(input);
+    let (value, unit) = input.split_at(input.char_indices().last()?.0);
+    let value: i64 = value.parse().ok()?;
+    match unit {
+        "s" => Duration::try_seconds(value),
+        "m" => Duration::try_minutes(value),
+        "h" => Duration::try_hours(value),
+        "d" => Duration::try_days(value),
+        "w" => Duration::try_weeks(value),
+        _ => None,
+    }
+}
+
+/// Time range of a historical search, kept as typed so that relative ranges follow the clock.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeRange {
+    pub start: TimeBound,
+    /// `None` means now.
+    pub end: Option<TimeBound>,
+    label: String,
+}
+
+impl TimeRange {
+    ///
+    /// parse a time range, with an optional end separated by `..`
+    /// ex. -15m, -2h..-1h, 2025-06-07 10:00..2025-06-07 11:00, 2025-06-07T01:00:00Z..
+    pub fn parse(input: &str, timezone: Tz) -> Result<Self, String> {
+        let input = input.trim();
+        let (start, end) = match input.split_once("..") {
+            Some((start, end)) => (start.trim(), Some(end.trim()).filter(|end| !end.is_empty())),
+            None => (input, None),
+        };
+        let time_range = Self {
+            start: TimeBound::parse(start, timezone)?,
+            end: end.map(|end| TimeBound::parse(end, timezone)).transpose()?,
+            label: input.to_string(),
+        };
+
+        let (start, end) = time_range.resolve(Utc::now());
+        if end.is_some_and(|end| end <= start) {
+            return Err(format!("The end of `{input}` is before the start"));
+        }
+        Ok(time_range)
+    }
+
+    pub fn resolve(&self, now: DateTime<Utc>) -> (DateTime<Utc>, Option<DateTime<Utc>>) {
+        (
+            self.start.resolve(now),
+            self.end.as_ref().map(|end| end.resolve(now)),
+        )
+    }
+}
+
+impl std::fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
     #[test]
     fn test_get_diff() {
-        let now = Utc::now().with_timezone(&Tokyo);
+        let now = Utc::now();
         let one_minute_ago = now - chrono::Duration::minutes(1);
         let five_minutes_ago = now - chrono::Duration::minutes(5);
         let one_hour_ago = now - chrono::Duration::hours(1);
@@ -47,4 +175,55 @@ mod test {
         assert_eq!(get_diff(one_month_ago), "1M");
         assert_eq!(get_diff(one_year_ago), "1y");
     }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone("Asia/Tokyo"), Ok(chrono_tz::Asia::Tokyo));
+        assert_eq!(parse_timezone("UTC"), Ok(Tz::UTC));
+        assert_eq!(parse_timezone("utc"), Ok(Tz::UTC));
+        assert!(parse_timezone("local").is_ok());
+        assert!(parse_timezone("Mars/Olympus_Mons").is_err());
+    }
+
+    #[test]
+    fn test_parse_relative_time_range() {
+        let now = Utc::now();
+        let time_range = TimeRange::parse("-15m", Tz::UTC).unwrap();
+        assert_eq!(time_range.resolve(now), (now - Duration::minutes(15), None));
+        assert_eq!(time_range.to_string(), "-15m");
+
+        let time_range = TimeRange::parse("2h..-1h", Tz::UTC).unwrap();
+        assert_eq!(
+            time_range.resolve(now),
+            (now - Duration::hours(2), Some(now - Duration::hours(1)))
+        );
+    }
+
+    #[test]
+    fn test_parse_absolute_time_range() {
+        let now = Utc::now();
+        let time_range =
+            TimeRange::parse("2025-06-07 10:00..2025-06-07 11:30", chrono_tz::Asia::Tokyo).unwrap();
+        assert_eq!(
+            time_range.resolve(now),
+            (
+                "2025-06-07T01:00:00Z".parse().unwrap(),
+                Some("2025-06-07T02:30:00Z".parse().unwrap())
+            )
+        );
+
+        let time_range = TimeRange::parse("2025-06-07T01:00:00Z..", Tz::UTC).unwrap();
+        assert_eq!(time_range.end, None);
+        assert_eq!(
+            TimeRange::parse("2025-06-07", Tz::UTC).unwrap().start,
+            TimeBound::At("2025-06-07T00:00:00Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_time_range() {
+        assert!(TimeRange::parse("yesterday", Tz::UTC).is_err());
+        assert!(TimeRange::parse("-1h..-2h", Tz::UTC).is_err());
+        assert!(TimeRange::parse("", Tz::UTC).is_err());
+    }
 }
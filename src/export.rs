@@ -0,0 +1,387 @@
+use std::{
+    fs,
+    io::Write,
+    path::{MAIN_SEPARATOR, PathBuf},
+};
+
+use chrono::{DateTime, SecondsFormat};
+use chrono_tz::Tz;
+use color_eyre::Result;
+use directories::BaseDirs;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{components::outer_layout::Message, config::ColumnConfig};
+
+/// File format of exported logs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object with the metadata per line
+    Ndjson,
+    JsonArray,
+    /// Metadata and the configured columns of the Log Stream
+    Csv,
+    /// Table for pasting into incident tickets
+    Markdown,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Ndjson,
+        ExportFormat::JsonArray,
+        ExportFormat::Csv,
+        ExportFormat::Markdown,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "NDJSON",
+            ExportFormat::JsonArray => "JSON array",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Markdown => "Markdown",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "jsonl",
+            ExportFormat::JsonArray => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// A log with its metadata. JSON messages are embedded as objects instead of strings.
+#[derive(Debug, Serialize)]
+struct ExportRecord<'a> {
+    timestamp: String,
+    log_group: &'a str,
+    log_stream: &'a str,
+    url: &'a str,
+    message: Value,
+}
+
+/// Writes logs in an `ExportFormat`.
+pub struct Exporter<'a> {
+    pub format: ExportFormat,
+    /// Extra columns of CSV and Markdown
+    pub columns: &'a [ColumnConfig],
+    pub timezone: Tz,
+}
+
+impl Exporter<'_> {
+    pub fn write(&self, writer: &mut impl Write, messages: &[Message]) -> Result<()> {
+        match self.format {
+            ExportFormat::Ndjson => {
+                for message in messages {
+                    serde_json::to_writer(&mut *writer, &self.record(message))?;
+                    writeln!(writer)?;
+                }
+            }
+            ExportFormat::JsonArray => {
+                let records: Vec<_> = messages
+                    .iter()
+                    .map(|message| self.record(message))
+                    .collect();
+                serde_json::to_writer_pretty(&mut *writer, &records)?;
+                writeln!(writer)?;
+            }
+            ExportFormat::Csv => {
+                for row in std::iter::once(self.header()).chain(self.rows(messages)) {
+                    let fields: Vec<_> = row.iter().map(|field| csv_field(field)).collect();
+                    // RFC 4180に従って改行はCRLF
+                    write!(writer, "{}\r\n", fields.join(","))?;
+                }
+            }
+            ExportFormat::Markdown => {
+                let header = self.header();
+                writeln!(writer, "| {} |", header.join(" | "))?;
+                writeln!(writer, "|{}", "---|".repeat(header.len()))?;
+                for row in self.rows(messages) {
+                    let cells: Vec<_> = row.iter().map(|cell| markdown_cell(cell)).collect();
+                    writeln!(writer, "| {} |", cells.join(" | "))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn timestamp(&self, message: &Message) -> String {
+        message
+            .datetime
+            .with_timezone(&self.timezone)
+            .to_rfc3339_opts(SecondsFormat::Millis, true)
+    }
+
+    fn record<'m>(&self, message: &'m Message) -> ExportRecord<'m> {
+        ExportRecord {
+            timestamp: self.timestamp(message),
+            log_group: &message.log_group,
+            log_stream: &message.log_stream,
+            url: &message.url,
+            message: serde_json::from_str(&message.content)
+                .unwrap_or_else(|_| Value::String(message.content.clone())),
+        }
+    }
+
+    fn header(&self) -> Vec<String> {
+        ["timestamp", "log_group", "log_stream"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(self.columns.iter().map(ColumnConfig::header))
+            .chain(["message".to_string(), "url".to_string()])
+            .collect()
+    }
+
+    fn rows<'m>(&'m self, messages: &'m [Message]) -> impl Iterator<Item = Vec<String>> + 'm {
+        messages.iter().map(|message| {
+            let json: Option<Value> = serde_json::from_str(&message.content).ok();
+            [
+                self.timestamp(message),
+                message.log_group.clone(),
+                message.log_stream.clone(),
+            ]
+            .into_iter()
+            .chain(
+                self.columns
+                    .iter()
+                    .map(|column| column.value(json.as_ref())),
+            )
+            .chain([message.content.clone(), message.url.clone()])
+            .collect()
+        })
+    }
+}
+
+/// Values of the placeholders in the filename template.
+pub struct FilenameContext<'a> {
+    pub datetime: DateTime<Tz>,
+    pub messages: &'a [Message],
+    pub format: ExportFormat,
+}
+
+/// Fill `{datetime}`, `{date}`, `{time}`, `{log_group}`, `{count}` and `{ext}` in the template.
+pub fn render_filename(template: &str, context: &FilenameContext) -> String {
+    let log_group = match context.messages.split_first() {
+        Some((first, rest))
+            if !first.log_group.is_empty()
+                && rest
+                    .iter()
+                    .all(|message| message.log_group == first.log_group) =>
+        {
+            // `/aws/lambda/app` -> `aws_lambda_app`
+            first.log_group.trim_start_matches('/').replace(
+                |c: char| !c.is_ascii_alphanumeric() && !"-_.".contains(c),
+                "_",
+            )
+        }
+        Some(_) => "multiple".to_string(),
+        None => "logs".to_string(),
+    };
+    template
+        .replace(
+            "{datetime}",
+            &context.datetime.format("%Y%m%d_%H%M%S").to_string(),
+        )
+        .replace("{date}", &context.datetime.format("%Y%m%d").to_string())
+        .replace("{time}", &context.datetime.format("%H%M%S").to_string())
+        .replace("{log_group}", &log_group)
+        .replace("{count}", &context.messages.len().to_string())
+        .replace("{ext}", context.format.extension())
+}
+
+/// Expand a leading `~` to the home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix('~')
+        && (rest.is_empty() || rest.starts_with(['/', MAIN_SEPARATOR]))
+        && let Some(base_dirs) = BaseDirs::new()
+    {
+        return base_dirs
+            .home_dir()
+            .join(rest.trim_start_matches(['/', MAIN_SEPARATOR]));
+    }
+    PathBuf::from(path)
+}
+
+/// Complete the last component of a path typed in a prompt, like a shell does on Tab.
+///
+/// Returns the input extended by the longest prefix common to the matching entries, with a
+/// trailing separator for a directory. `None` if nothing can be added.
+pub fn complete_path(input: &str) -> Option<String> {
+    let (dir, prefix) = match input.rfind(['/', MAIN_SEPARATOR]) {
+        Some(index) => input.split_at(index + 1),
+        None => ("", input),
+    };
+    let entries = fs::read_dir(if dir.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_home(dir)
+    })
+    .ok()?;
+
+    let mut candidates: Vec<String> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().into_string().ok()?;
+            // 隠しファイルは`.`を入力したときだけ補完する
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            Some(if entry.path().is_dir() {
+                format!("{name}{MAIN_SEPARATOR}")
+            } else {
+                name
+            })
+        })
+        .collect();
+    candidates.sort();
+
+    let first = candidates.first()?;
+    let common_len = candidates
+        .iter()
+        .skip(1)
+        .fold(first.len(), |len, candidate| {
+            first[..len]
+                .char_indices()
+                .zip(candidate.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or(len.min(candidate.len()), |((index, _), _)| index)
+        });
+    (common_len > prefix.len()).then(|| format!("{dir}{}", &first[..common_len]))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn markdown_cell(cell: &str) -> String {
+    cell.trim_end()
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::components::outer_layout::message;
+
+    fn messages() -> Vec<Message> {
+        [
+            "{\"level\":\"ERROR\",\"requestId\":\"abc\"}",
+            "plain, \"quoted\"\nline",
+        ]
+        .into_iter()
+        .map(|content| Message {
+            datetime: Utc.with_ymd_and_hms(2025, 6, 7, 10, 0, 0).unwrap(),
+            log_group: "/aws/lambda/app".to_string(),
+            log_stream: "stream|1".to_string(),
+            url: "https://example.com".to_string(),
+            ..message(content)
+        })
+        .collect()
+    }
+
+    fn export(format: ExportFormat) -> String {
+        let columns: Vec<ColumnConfig> =
+            json5::from_str(r#"[{ path: "$.requestId", title: "Request" }]"#).unwrap();
+        let exporter = Exporter {
+            format,
+            columns: &columns,
+            timezone: Tz::UTC,
+        };
+        let mut exported = Vec::new();
+        exporter.write(&mut exported, &messages()).unwrap();
+        String::from_utf8(exported).unwrap()
+    }
+
+    #[test]
+    fn test_ndjson_and_json_array() {
+        let ndjson = export(ExportFormat::Ndjson);
+        let lines: Vec<Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["timestamp"], "2025-06-07T10:00:00.000Z");
+        assert_eq!(lines[0]["log_group"], "/aws/lambda/app");
+        assert_eq!(lines[0]["message"]["requestId"], "abc");
+        assert_eq!(lines[1]["message"], "plain, \"quoted\"\nline");
+
+        let array: Value = serde_json::from_str(&export(ExportFormat::JsonArray)).unwrap();
+        assert_eq!(array, Value::Array(lines));
+    }
+
+    #[test]
+    fn test_csv_and_markdown() {
+        assert_eq!(
+            export(ExportFormat::Csv),
+            "timestamp,log_group,log_stream,Request,message,url\r\n\
+             2025-06-07T10:00:00.000Z,/aws/lambda/app,stream|1,abc,\"{\"\"level\"\":\"\"ERROR\"\",\"\"requestId\"\":\"\"abc\"\"}\",https://example.com\r\n\
+             2025-06-07T10:00:00.000Z,/aws/lambda/app,stream|1,,\"plain, \"\"quoted\"\"\nline\",https://example.com\r\n"
+        );
+        assert_eq!(
+            export(ExportFormat::Markdown),
+            "| timestamp | log_group | log_stream | Request | message | url |\n\
+             |---|---|---|---|---|---|\n\
+             | 2025-06-07T10:00:00.000Z | /aws/lambda/app | stream\\|1 | abc | {\"level\":\"ERROR\",\"requestId\":\"abc\"} | https://example.com |\n\
+             | 2025-06-07T10:00:00.000Z | /aws/lambda/app | stream\\|1 |  | plain, \"quoted\"<br>line | https://example.com |\n"
+        );
+    }
+
+    #[test]
+    fn test_render_filename() {
+        let context = FilenameContext {
+            datetime: Tz::Asia__Tokyo
+                .with_ymd_and_hms(2025, 6, 7, 18, 9, 19)
+                .unwrap(),
+            messages: &messages(),
+            format: ExportFormat::Csv,
+        };
+        assert_eq!(
+            render_filename("{log_group}_{datetime}_{count}.{ext}", &context),
+            "aws_lambda_app_20250607_180919_2.csv"
+        );
+        assert_eq!(
+            render_filename("{date}/{time}.{ext}", &context),
+            "20250607/180919.csv"
+        );
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("exports")).unwrap();
+        fs::write(root.join("export.csv"), "").unwrap();
+        fs::write(root.join("errors.md"), "").unwrap();
+        fs::write(root.join(".hidden"), "").unwrap();
+        let root = format!("{}{MAIN_SEPARATOR}", root.display());
+
+        assert_eq!(
+            complete_path(&format!("{root}exp")),
+            Some(format!("{root}export"))
+        );
+        assert_eq!(
+            complete_path(&format!("{root}exports")),
+            Some(format!("{root}exports{MAIN_SEPARATOR}"))
+        );
+        assert_eq!(
+            complete_path(&format!("{root}er")),
+            Some(format!("{root}errors.md"))
+        );
+        assert_eq!(complete_path(&format!("{root}e")), None);
+        assert_eq!(complete_path(&format!("{root}x")), None);
+        assert_eq!(
+            complete_path(&format!("{root}.")),
+            Some(format!("{root}.hidden"))
+        );
+    }
+}
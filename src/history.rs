@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::{
+    action::{Action, ComponentAction},
+    backend::{FetchEventsRequest, LiveTailRequest, LogBackend, is_permanent},
+    components::outer_layout::Message,
+    date::TimeRange,
+};
+
+/// Sent when a page of a historical search is requested.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchEvents {
+    /// A new search replaces the logs in the table.
+    pub first_page: bool,
+}
+impl ComponentAction for SearchEvents {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "SearchEvents"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReceiveEventsPage {
+    pub messages: Vec<Message>,
+    /// Requests for the following pages. Empty once every log group is exhausted.
+    pub next_requests: Vec<FetchEventsRequest>,
+}
+impl ComponentAction for ReceiveEventsPage {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "ReceiveEventsPage"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+}
+
+/// Build the requests for the first page of a search with the same targets as the live tail.
+///
+/// FilterLogEvents takes a single log group and a single stream prefix, so one request is made
+/// per log group, or per prefix when prefixes are given.
+pub fn first_requests(
+    request: &LiveTailRequest,
+    time_range: &TimeRange,
+    now: DateTime<Utc>,
+) -> Vec<FetchEventsRequest> {
+    let (start_time, end_time) = time_range.resolve(now);
+    let base = FetchEventsRequest {
+        start_time: Some(start_time),
+        end_time,
+        filter_pattern: request.log_event_filter_pattern.clone(),
+        log_stream_names: request.log_stream_names.clone(),
+        ..Default::default()
+    };
+
+    request
+        .log_group_identifiers
+        .iter()
+        .flat_map(|log_group_identifier| {
+            let base = FetchEventsRequest {
+                log_group_identifier: log_group_identifier.clone(),
+                ..base.clone()
+            };
+            if request.log_stream_name_prefixes.is_empty() {
+                vec![base]
+            } else {
+                request
+                    .log_stream_name_prefixes
+                    .iter()
+                    .map(|prefix| FetchEventsRequest {
+                        log_stream_name_prefix: Some(prefix.clone()),
+                        ..base.clone()
+                    })
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+/// Fetch one page for every request and send the merged result.
+///
+/// Failed requests are reported and kept in `next_requests` so that the next page retries them.
+pub async fn fetch_page(
+    backend: Arc<dyn LogBackend>,
+    requests: Vec<FetchEventsRequest>,
+    tx: UnboundedSender<Action>,
+    cancel_token: CancellationToken,
+) {
+    let pages = futures::future::join_all(requests.into_iter().map(|request| {
+        let backend = backend.clone();
+        async move { (request.clone(), backend.fetch_events(request).await) }
+    }));
+    let pages = tokio::select! {
+        _ = cancel_token.cancelled() => {
+            debug!("Search cancelled");
+            return;
+        }
+        pages = pages => pages,
+    };
+
+    let mut messages = Vec::new();
+    let mut next_requests = Vec::new();
+    for (request, page) in pages {
+        match page {
+            Ok(page) => {
+                messages.extend(page.messages);
+                if let Some(next_token) = page.next_token {
+                    next_requests.push(FetchEventsRequest {
+                        next_token: Some(next_token),
+                        ..request
+                    });
+                }
+            }
+            Err(e) => {
+                debug!("Failed to fetch events: {:?}", e);
+                let _ = tx.send(Action::Error(format!(
+                    "Failed to search {}: {e:#}",
+                    request.log_group_identifier
+                )));
+                // 権限がない等の失敗は再試行しても変わらないので、続きを取得しない
+                if !is_permanent(&e) {
+                    next_requests.push(request);
+                }
+            }
+        }
+    }
+    // 複数のロググループの結果を時系列に並べる
+    messages.sort_by_key(|message| message.datetime);
+
+    let _ = tx.send(Action::ComponentAction(Box::new(ReceiveEventsPage {
+        messages,
+        next_requests,
+    })));
+}
+
+#[cfg(test)]
+mod test {
+    use chrono_tz::Tz;
+
+    use super::*;
+    use crate::backend::fake::FakeLogBackend;
+
+    #[tokio::test]
+    async fn test_fetch_page_retries_transient_errors() {
+        let backend = FakeLogBackend::new()
+            .events_error("denied", "AccessDeniedException", true)
+            .events_error("flaky", "ThrottlingException", false);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let requests = ["denied", "flaky", "ok"]
+            .into_iter()
+            .map(|log_group| FetchEventsRequest {
+                log_group_identifier: log_group.to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        fetch_page(Arc::new(backend), requests, tx, CancellationToken::new()).await;
+
+        let mut errors = 0;
+        let mut next_requests = None;
+        while let Ok(action) = rx.try_recv() {
+            match action {
+                Action::Error(_) => errors += 1,
+                Action::ComponentAction(action) => {
+                    let page = action.as_any().downcast_ref::<ReceiveEventsPage>().unwrap();
+                    next_requests = Some(page.next_requests.clone());
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(errors, 2);
+        // 一時的な失敗だけを次のページで再試行する
+        let next_requests = next_requests.unwrap();
+        assert_eq!(next_requests.len(), 1);
+        assert_eq!(next_requests[0].log_group_identifier, "flaky");
+    }
+
+    #[test]
+    fn test_first_requests() {
+        let now = Utc::now();
+        let time_range = TimeRange::parse("-15m", Tz::UTC).unwrap();
+        let request = LiveTailRequest {
+            log_group_identifiers: vec!["foo".to_string()],
+            log_event_filter_pattern: Some("ERROR".to_string()),
+            log_stream_name_prefixes: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+
+        let requests = first_requests(&request, &time_range, now);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[1],
+            FetchEventsRequest {
+                log_group_identifier: "foo".to_string(),
+                start_time: Some(now - chrono::Duration::minutes(15)),
+                end_time: None,
+                filter_pattern: Some("ERROR".to_string()),
+                log_stream_name_prefix: Some("b".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+}
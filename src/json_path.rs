@@ -0,0 +1,137 @@
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Path to a value in a JSON log, e.g. `$.requestId`, `$.http.status` or `$.items[0]["a.b"]`.
+#[derive(Clone, Debug, Eq)]
+pub struct JsonPath {
+    raw: String,
+    segments: Vec<Segment>,
+}
+
+/// `requestId` and `$.requestId` are the same path.
+impl PartialEq for JsonPath {
+    fn eq(&self, other: &Self) -> bool {
+        self.segments == other.segments
+    }
+}
+
+impl JsonPath {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let invalid = |reason: &str| format!("Invalid JSON path '{raw}': {reason}");
+
+        let trimmed = raw.trim();
+        let normalized = match trimmed.strip_prefix('$') {
+            Some(rest) => rest.to_string(),
+            // `$.`は省略できる
+            None => format!(".{trimmed}"),
+        };
+
+        let mut segments = Vec::new();
+        let mut rest = normalized.as_str();
+        while !rest.is_empty() {
+            if let Some(bracket) = rest.strip_prefix('[') {
+                let (inner, after) = bracket
+                    .split_once(']')
+                    .ok_or_else(|| invalid("missing ']'"))?;
+                let segment = match inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+                    Some(key) => Segment::Key(key.to_string()),
+                    None => Segment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| invalid("index must be a number or a quoted key"))?,
+                    ),
+                };
+                segments.push(segment);
+                rest = after;
+            } else if let Some(key_part) = rest.strip_prefix('.') {
+                let end = key_part.find(['.', '[']).unwrap_or(key_part.len());
+                if end == 0 {
+                    return Err(invalid("empty key"));
+                }
+                segments.push(Segment::Key(key_part[..end].to_string()));
+                rest = &key_part[end..];
+            } else {
+                return Err(invalid("expected '.' or '['"));
+            }
+        }
+        if segments.is_empty() {
+            return Err(invalid("empty path"));
+        }
+
+        Ok(Self {
+            raw: trimmed.to_string(),
+            segments,
+        })
+    }
+
+    pub fn get<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(value, |value, segment| match segment {
+                Segment::Key(key) => value.get(key),
+                Segment::Index(index) => value.get(index),
+            })
+    }
+
+    /// Last key of the path, used as the default column title.
+    pub fn last_key(&self) -> &str {
+        self.segments
+            .iter()
+            .rev()
+            .find_map(|segment| match segment {
+                Segment::Key(key) => Some(key.as_str()),
+                Segment::Index(_) => None,
+            })
+            .unwrap_or(&self.raw)
+    }
+}
+
+impl std::fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_and_get() {
+        let value = json!({
+            "requestId": "abc",
+            "http": {"status": 500},
+            "items": [{"a.b": true}],
+        });
+
+        for (raw, expected) in [
+            ("$.requestId", json!("abc")),
+            ("requestId", json!("abc")),
+            ("$.http.status", json!(500)),
+            ("http.status", json!(500)),
+            ("$.items[0][\"a.b\"]", json!(true)),
+        ] {
+            let path = JsonPath::parse(raw).unwrap();
+            assert_eq!(path.get(&value), Some(&expected), "{raw}");
+        }
+        assert_eq!(JsonPath::parse("$.http.missing").unwrap().get(&value), None);
+        assert_eq!(
+            JsonPath::parse("$.http.status").unwrap().last_key(),
+            "status"
+        );
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        for raw in ["", "$", "$.", "$..a", "$.a[", "$.a[x]", "$a", "a..b"] {
+            assert!(JsonPath::parse(raw).is_err(), "{raw}");
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
+use futures::StreamExt;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_util::sync::CancellationToken;
+use tracing::debug;
+
+use crate::{
+    action::{Action, ComponentAction},
+    backend::{LiveTailRequest, LogBackend, is_permanent},
+    components::outer_layout::{Message, ReceiveNewLog},
+};
+
+/// Give up after this many consecutive failed attempts.
+const MAX_RECONNECT_ATTEMPTS: u32 = 8;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Number of recent events remembered to drop the overlap after a reconnect.
+const DEDUP_CAPACITY: usize = 10_000;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum LiveTailStatus {
+    /// No log group is selected.
+    #[default]
+    Idle,
+    Connecting,
+    Live,
+    Reconnecting {
+        attempt: u32,
+        retry_in: Duration,
+    },
+    Disconnected(String),
+}
+
+impl std::fmt::Display for LiveTailStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LiveTailStatus::Idle => write!(f, "idle"),
+            LiveTailStatus::Connecting => write!(f, "connecting"),
+            LiveTailStatus::Live => write!(f, "live"),
+            LiveTailStatus::Reconnecting { attempt, retry_in } => {
+                write!(f, "reconnecting #{} in {}s", attempt, retry_in.as_secs())
+            }
+            LiveTailStatus::Disconnected(reason) => {
+                write!(f, "disconnected: {} - press r to reconnect", reason)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeLiveTailStatus {
+    pub status: LiveTailStatus,
+}
+impl ComponentAction for ChangeLiveTailStatus {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &'static str {
+        "ChangeLiveTailStatus"
+    }
+
+    fn clone_box(&self) -> Box<dyn ComponentAction> {
+        Box::new(self.clone())
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    if attempt == 0 {
+        return Duration::ZERO;
+    }
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// Remembers recently delivered events so that a resumed session does not repeat them.
+#[derive(Debug, Default)]
+struct RecentEvents {
+    keys: HashSet<u64>,
+    order: VecDeque<u64>,
+    latest: Option<DateTime<Utc>>,
+}
+
+impl RecentEvents {
+    fn key(message: &Message) -> u64 {
+        // idは受信時に振られるので、タイムスタンプ・グループ・ストリーム・本文で判定する
+        // URLはリージョンが分からないと空なので使わない
+        let mut hasher = DefaultHasher::new();
+        (
+            message.datetime,
+            &message.log_group,
+            &message.log_stream,
+            &message.content,
+        )
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Drop events already delivered before the reconnect, then remember the rest.
+    fn dedup(&mut self, messages: Vec<Message>, resumed: bool) -> Vec<Message> {
+        let messages: Vec<Message> = messages
+            .into_iter()
+            .filter(|message| {
+                !resumed
+                    || self.latest.is_none_or(|latest| message.datetime > latest)
+                    || !self.keys.contains(&Self::key(message))
+            })
+            .collect();
+
+        for message in &messages {
+            let key = Self::key(message);
+            if self.keys.insert(key) {
+                self.order.push_back(key);
+            }
+            self.latest = self.latest.max(Some(message.datetime));
+        }
+        while self.order.len() > DEDUP_CAPACITY {
+            if let Some(key) = self.order.pop_front() {
+                self.keys.remove(&key);
+            }
+        }
+        messages
+    }
+}
+
+fn send_status(tx: &UnboundedSender<Action>, status: LiveTailStatus) -> bool {
+    tx.send(Action::ComponentAction(Box::new(ChangeLiveTailStatus {
+        status,
+    })))
+    .is_ok()
+}
+
+/// Run a live tail session until cancelled, reconnecting with exponential backoff.
+///
+/// Sessions end on their own after three hours or when the connection drops. A session that
+/// delivered updates is resumed immediately, otherwise the delay grows with each failed attempt.
+/// Errors that retrying can't fix, such as denied access, stop it at once. The error is reported
+/// only when it gives up, the attempts before are shown in the status.
+pub async fn run(
+    backend: Arc<dyn LogBackend>,
+    request: LiveTailRequest,
+    tx: UnboundedSender<Action>,
+    cancel_token: CancellationToken,
+) {
+    let mut recent_events = RecentEvents::default();
+    let mut attempt = 0;
+    let mut resumed = false;
+
+    loop {
+        if cancel_token.is_cancelled() || !send_status(&tx, LiveTailStatus::Connecting) {
+            return;
+        }
+
+        // 接続中にキャンセルされたら、新しいセッションの状態を上書きしないように何も送らない
+        let started = tokio::select! {
+            biased;
+            _ = cancel_token.cancelled() => {
+                debug!("Live tail cancelled while connecting");
+                return;
+            }
+            started = backend.start_live_tail(request.clone()) => started,
+        };
+        let error = match started {
+            Ok(mut stream) => {
+                if cancel_token.is_cancelled() || !send_status(&tx, LiveTailStatus::Live) {
+                    return;
+                }
+                loop {
+                    tokio::select! {
+                        biased;
+                        // キャンセルシグナルを監視
+                        _ = cancel_token.cancelled() => {
+                            debug!("Live tail cancelled");
+                            return;
+                        }
+                        // ストリームからのデータを処理
+                        result = stream.next() => {
+                            match result {
+                                Some(Ok(new_messages)) => {
+                                    attempt = 0;
+                                    let new_messages = recent_events.dedup(new_messages, resumed);
+                                    if new_messages.is_empty() {
+                                        debug!("No new messages in this log event.");
+                                        continue;
+                                    }
+
+                                    debug!("Received new_messages: {:?}", &new_messages);
+                                    if tx.send(Action::ComponentAction(Box::new(ReceiveNewLog {
+                                        new_messages,
+                                    }))).is_err() {
+                                        debug!("Failed to send new messages - receiver dropped");
+                                        return;
+                                    }
+                                }
+                                Some(Err(e)) => {
+                                    debug!("Error receiving log events: {:?}", e);
+                                    break e.wrap_err("Live Tail failed");
+                                }
+                                None => {
+                                    debug!("Live tail session ended.");
+                                    break eyre!("Live Tail session ended");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                debug!("Failed to start live tail: {:?}", e);
+                e.wrap_err("Failed to start Live Tail")
+            }
+        };
+        resumed = true;
+        if cancel_token.is_cancelled() {
+            return;
+        }
+
+        if is_permanent(&error) || attempt >= MAX_RECONNECT_ATTEMPTS {
+            let _ = tx.send(Action::Error(format!("{error:#}")));
+            send_status(
+                &tx,
+                LiveTailStatus::Disconnected(error.root_cause().to_string()),
+            );
+            return;
+        }
+        let retry_in = backoff(attempt);
+        attempt += 1;
+        if retry_in.is_zero() {
+            continue;
+        }
+        if !send_status(&tx, LiveTailStatus::Reconnecting { attempt, retry_in }) {
+            return;
+        }
+
+        tokio::select! {
+            biased;
+            _ = cancel_token.cancelled() => {
+                debug!("Live tail cancelled while reconnecting");
+                return;
+            }
+            _ = tokio::time::sleep(retry_in) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{backend::fake::FakeLogBackend, components::outer_layout::message};
+
+    fn message_at(content: &str, seconds: i64) -> Message {
+        Message {
+            datetime: DateTime::from_timestamp(seconds, 0).unwrap(),
+            log_group: "/aws/lambda/app".to_string(),
+            log_stream: "stream".to_string(),
+            ..message(content)
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0), Duration::ZERO);
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(4));
+        assert_eq!(backoff(10), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_dedup_after_resume() {
+        let mut recent_events = RecentEvents::default();
+        let first = recent_events.dedup(vec![message_at("a", 1), message_at("b", 2)], false);
+        assert_eq!(first.len(), 2);
+
+        // 同じ時刻と本文でも別のストリームのログは残す
+        let other_stream = Message {
+            log_stream: "other".to_string(),
+            ..message_at("b", 2)
+        };
+        let resumed = recent_events.dedup(
+            vec![
+                message_at("b", 2),
+                other_stream,
+                message_at("c", 2),
+                message_at("d", 3),
+            ],
+            true,
+        );
+        let contents: Vec<_> = resumed
+            .iter()
+            .map(|m| (m.log_stream.as_str(), m.content.as_str()))
+            .collect();
+        assert_eq!(
+            contents,
+            vec![("other", "b"), ("stream", "c"), ("stream", "d")]
+        );
+    }
+
+    /// Run until the live tail gives up and return the errors and the final status.
+    async fn run_until_disconnected(backend: Arc<FakeLogBackend>) -> (Vec<String>, LiveTailStatus) {
+        run_with_token(backend, CancellationToken::new()).await
+    }
+
+    async fn run_with_token(
+        backend: Arc<FakeLogBackend>,
+        cancel_token: CancellationToken,
+    ) -> (Vec<String>, LiveTailStatus) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        run(backend, LiveTailRequest::default(), tx, cancel_token).await;
+
+        let mut errors = Vec::new();
+        let mut status = LiveTailStatus::Idle;
+        while let Ok(action) = rx.try_recv() {
+            match action {
+                Action::Error(error) => errors.push(error),
+                Action::ComponentAction(action) => {
+                    if let Some(change) = action.as_any().downcast_ref::<ChangeLiveTailStatus>() {
+                        status = change.status.clone();
+                    }
+                }
+                _ => {}
+            }
+        }
+        (errors, status)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_permanent_error_fails_fast() {
+        let backend =
+            Arc::new(FakeLogBackend::new().live_tail_error("AccessDeniedException", true));
+        let (errors, status) = run_until_disconnected(backend.clone()).await;
+
+        assert_eq!(backend.live_tail_requests().len(), 1);
+        assert_eq!(
+            errors,
+            vec!["Failed to start Live Tail: AccessDeniedException"]
+        );
+        assert_eq!(
+            status,
+            LiveTailStatus::Disconnected("AccessDeniedException".to_string())
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_transient_error_reports_once() {
+        let backend = Arc::new(FakeLogBackend::new().live_tail_error("connection reset", false));
+        let (errors, status) = run_until_disconnected(backend.clone()).await;
+
+        // 再接続の途中ではエラーを通知せず、諦めたときだけ通知する
+        assert_eq!(
+            backend.live_tail_requests().len(),
+            MAX_RECONNECT_ATTEMPTS as usize + 1
+        );
+        assert_eq!(errors, vec!["Failed to start Live Tail: connection reset"]);
+        assert_eq!(
+            status,
+            LiveTailStatus::Disconnected("connection reset".to_string())
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancelled_while_connecting() {
+        let backend = Arc::new(
+            FakeLogBackend::new()
+                .live_tail_error("AccessDeniedException", true)
+                .live_tail_delay(Duration::from_secs(5)),
+        );
+        let cancel_token = CancellationToken::new();
+        let task = tokio::spawn(run_with_token(backend.clone(), cancel_token.clone()));
+        // 接続が返る前にプロファイルが切り替わった
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        cancel_token.cancel();
+        let (errors, status) = task.await.unwrap();
+
+        // 古いセッションのエラーや状態で新しいセッションを上書きしない
+        assert_eq!(backend.live_tail_requests().len(), 1);
+        assert!(errors.is_empty());
+        assert_eq!(status, LiveTailStatus::Connecting);
+    }
+}
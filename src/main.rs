@@ -1,18 +1,27 @@
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command};
 use color_eyre::Result;
 
-use crate::app::App;
+use crate::app::{App, LocalSource};
 
 mod action;
 mod app;
+mod backend;
 mod cli;
+mod clipboard;
 mod components;
 mod config;
 mod date;
 mod errors;
+mod export;
+mod history;
+mod json_path;
+mod live_tail;
 mod logging;
 mod notification;
+mod replay;
+mod scrollback;
+mod tail;
 mod tui;
 
 #[tokio::main]
@@ -21,7 +30,26 @@ async fn main() -> Result<()> {
     crate::logging::init()?;
 
     let args = Cli::parse();
-    let mut app = App::new(args.tick_rate, args.frame_rate)?;
+    let source = match args.command {
+        Some(Command::Open {
+            file,
+            replay,
+            speed,
+        }) => Some(LocalSource::File(replay::FileSource {
+            path: file,
+            speed: replay.then_some(speed),
+        })),
+        None if !args.files.is_empty() => Some(LocalSource::Tail(args.files)),
+        None => None,
+    };
+    let mut app = App::new(
+        args.tick_rate,
+        args.frame_rate,
+        args.region,
+        args.profile,
+        args.tz,
+        source,
+    )?;
     app.run().await?;
     Ok(())
 }
@@ -1,5 +1,205 @@
-use mac_notification_sys::*;
+use std::io::Write;
 
-pub fn show_notification(title: &str, body: &str) {
-    send_notification(title, Some("cwlogs-viewer"), body, None).expect("can't show notification");
+use color_eyre::{Result, eyre::eyre};
+use serde::Deserialize;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{action::Action, components::toast::ShowToast};
+
+/// Where notifications are delivered, set with `notification` in the config.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationBackend {
+    /// The desktop notification of the platform if built in, otherwise the toast
+    #[default]
+    Auto,
+    Macos,
+    /// `org.freedesktop.Notifications` through `notify-send`
+    Freedesktop,
+    /// Ring the terminal bell along with the toast
+    Bell,
+    /// Toast inside the TUI
+    Toast,
+}
+
+pub trait Notifier {
+    fn notify(&self, title: &str, body: &str) -> Result<()>;
+}
+
+#[cfg(all(target_os = "macos", feature = "macos-notification"))]
+struct MacosNotifier;
+
+#[cfg(all(target_os = "macos", feature = "macos-notification"))]
+impl Notifier for MacosNotifier {
+    fn notify(&self, title: &str, body: &str) -> Result<()> {
+        mac_notification_sys::send_notification(title, Some("cwlogs-viewer"), body, None)?;
+        Ok(())
+    }
+}
+
+/// Calls `notify-send` of libnotify instead of linking a D-Bus client.
+///
+/// `notify-send` can block until the D-Bus timeout when no notification daemon is running, so it
+/// runs in the background and a failure is shown as the toast once it exits.
+#[cfg(all(unix, not(target_os = "macos"), feature = "freedesktop-notification"))]
+struct FreedesktopNotifier {
+    tx: UnboundedSender<Action>,
+}
+
+#[cfg(all(unix, not(target_os = "macos"), feature = "freedesktop-notification"))]
+impl Notifier for FreedesktopNotifier {
+    fn notify(&self, title: &str, body: &str) -> Result<()> {
+        let runtime = tokio::runtime::Handle::try_current()?;
+        let child = tokio::process::Command::new("notify-send")
+            .arg("--app-name=cwlogs-viewer")
+            .arg(title)
+            .arg(body)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| eyre!("Failed to run notify-send: {e}"))?;
+
+        let (tx, title, body) = (self.tx.clone(), title.to_string(), body.to_string());
+        runtime.spawn(async move {
+            let result = match child.wait_with_output().await {
+                Ok(output) if output.status.success() => Ok(()),
+                Ok(output) => Err(eyre!(
+                    "notify-send failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )),
+                Err(e) => Err(eyre!("Failed to run notify-send: {e}")),
+            };
+            if let Err(e) = result {
+                fall_back_to_toast(&tx, &title, &body, &e);
+            }
+        });
+        Ok(())
+    }
+}
+
+struct ToastNotifier<'a> {
+    tx: &'a UnboundedSender<Action>,
+}
+
+impl Notifier for ToastNotifier<'_> {
+    fn notify(&self, title: &str, body: &str) -> Result<()> {
+        self.tx.send(Action::ComponentAction(Box::new(ShowToast {
+            title: title.to_string(),
+            body: body.to_string(),
+        })))?;
+        Ok(())
+    }
+}
+
+struct BellNotifier<'a> {
+    toast: ToastNotifier<'a>,
+}
+
+impl Notifier for BellNotifier<'_> {
+    fn notify(&self, title: &str, body: &str) -> Result<()> {
+        // TUIと同じstdoutに書き込む
+        let mut stdout = std::io::stdout();
+        stdout.write_all(b"\x07")?;
+        stdout.flush()?;
+        self.toast.notify(title, body)
+    }
+}
+
+impl NotificationBackend {
+    /// `Auto` is the desktop notification built into this binary, if any.
+    fn resolve(self) -> Self {
+        if self != NotificationBackend::Auto {
+            return self;
+        }
+        if cfg!(all(target_os = "macos", feature = "macos-notification")) {
+            NotificationBackend::Macos
+        } else if cfg!(all(
+            unix,
+            not(target_os = "macos"),
+            feature = "freedesktop-notification"
+        )) {
+            NotificationBackend::Freedesktop
+        } else {
+            NotificationBackend::Toast
+        }
+    }
+
+    fn notifier<'a>(self, tx: &'a UnboundedSender<Action>) -> Result<Box<dyn Notifier + 'a>> {
+        match self.resolve() {
+            #[cfg(all(target_os = "macos", feature = "macos-notification"))]
+            NotificationBackend::Macos => Ok(Box::new(MacosNotifier)),
+            #[cfg(all(unix, not(target_os = "macos"), feature = "freedesktop-notification"))]
+            NotificationBackend::Freedesktop => {
+                Ok(Box::new(FreedesktopNotifier { tx: tx.clone() }))
+            }
+            NotificationBackend::Bell => Ok(Box::new(BellNotifier {
+                toast: ToastNotifier { tx },
+            })),
+            NotificationBackend::Toast | NotificationBackend::Auto => {
+                Ok(Box::new(ToastNotifier { tx }))
+            }
+            #[allow(unreachable_patterns)]
+            backend => Err(eyre!(
+                "{backend:?} notifications are not available in this build"
+            )),
+        }
+    }
+}
+
+/// Notify with the configured backend. Failures fall back to the toast and never stop the app.
+pub fn show_notification(
+    backend: NotificationBackend,
+    tx: &UnboundedSender<Action>,
+    title: &str,
+    body: &str,
+) {
+    let result = backend
+        .notifier(tx)
+        .and_then(|notifier| notifier.notify(title, body));
+    if let Err(e) = result {
+        fall_back_to_toast(tx, title, body, &e);
+    }
+}
+
+fn fall_back_to_toast(
+    tx: &UnboundedSender<Action>,
+    title: &str,
+    body: &str,
+    error: &color_eyre::Report,
+) {
+    tracing::warn!("Failed to show notification: {error:#}");
+    let _ = ToastNotifier { tx }.notify(title, body);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_toast_fallback() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        show_notification(NotificationBackend::Toast, &tx, "Copy", "Copied");
+
+        let Action::ComponentAction(action) = rx.try_recv().unwrap() else {
+            panic!("expected a toast");
+        };
+        assert_eq!(
+            action.as_any().downcast_ref::<ShowToast>(),
+            Some(&ShowToast {
+                title: "Copy".to_string(),
+                body: "Copied".to_string(),
+            })
+        );
+
+        // 組み込まれていないバックエンドはトーストで代替する
+        let unavailable = if cfg!(target_os = "macos") {
+            NotificationBackend::Freedesktop
+        } else {
+            NotificationBackend::Macos
+        };
+        assert!(unavailable.notifier(&tx).is_err());
+        show_notification(unavailable, &tx, "Copy", "Copied");
+        assert!(rx.try_recv().is_ok());
+    }
 }
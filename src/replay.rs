@@ -0,0 +1,314 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{Result, eyre::eyre};
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+use ulid::Ulid;
+
+use crate::{
+    action::Action,
+    components::{
+        outer_layout::{Message, ReceiveNewLog},
+        toast::ShowToast,
+    },
+};
+
+/// File given to `open`.
+pub struct FileSource {
+    pub path: PathBuf,
+    /// Replay speed, or `None` to load all the logs at once
+    pub speed: Option<f64>,
+}
+
+/// JSON fields holding the time of a log written by common loggers.
+const TIMESTAMP_FIELDS: [&str; 4] = ["timestamp", "@timestamp", "time", "ts"];
+
+/// Read the logs of a file exported by the Log Stream, or of plain text with one log per line.
+///
+/// NDJSON and JSON array exports keep their metadata. Other lines become logs as they are, timed
+/// by a timestamp field or a leading timestamp if they have one.
+pub fn read_logs(path: &Path) -> Result<Vec<Message>> {
+    let text =
+        fs::read_to_string(path).map_err(|e| eyre!("Failed to read {}: {e}", path.display()))?;
+
+    let mut timed: Vec<(Option<DateTime<Utc>>, Message)> =
+        match serde_json::from_str::<Value>(text.trim_start()) {
+            Ok(Value::Array(records)) if text.trim_start().starts_with('[') => records
+                .into_iter()
+                .map(|record| match record {
+                    Value::String(line) => parse_line(&line),
+                    record => parse_value(record, None),
+                })
+                .collect(),
+            _ => text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(parse_line)
+                .collect(),
+        };
+
+    // 時刻が無い行は直前の行の時刻にする
+    let first = timed.iter().find_map(|(datetime, _)| *datetime);
+    let mut previous = first.unwrap_or_else(Utc::now);
+    for (datetime, message) in &mut timed {
+        message.datetime = datetime.unwrap_or(previous);
+        previous = message.datetime;
+    }
+    Ok(timed.into_iter().map(|(_, message)| message).collect())
+}
+
+/// A JSON line keeps its metadata and timestamp field, other lines their leading timestamp.
+pub fn parse_line(line: &str) -> (Option<DateTime<Utc>>, Message) {
+    match serde_json::from_str::<Value>(line) {
+        Ok(value @ Value::Object(_)) => parse_value(value, Some(line)),
+        _ => (leading_timestamp(line), message(line.to_string())),
+    }
+}
+
+fn message(content: String) -> Message {
+    Message {
+        id: Ulid::new(),
+        content,
+        datetime: DateTime::UNIX_EPOCH,
+        log_group: String::new(),
+        log_stream: String::new(),
+        url: String::new(),
+    }
+}
+
+/// `line` is kept as the content unless the value is an export with metadata.
+fn parse_value(value: Value, line: Option<&str>) -> (Option<DateTime<Utc>>, Message) {
+    // エクスポートしたNDJSONはメタデータを持っている
+    if let Value::Object(object) = &value
+        && let (
+            Some(message_value),
+            Some(Value::String(log_group)),
+            Some(Value::String(log_stream)),
+        ) = (
+            object.get("message"),
+            object.get("log_group"),
+            object.get("log_stream"),
+        )
+    {
+        let content = match message_value {
+            Value::String(content) => content.clone(),
+            value => value.to_string(),
+        };
+        let url = match object.get("url") {
+            Some(Value::String(url)) => url.clone(),
+            _ => String::new(),
+        };
+        return (
+            object.get("timestamp").and_then(json_timestamp),
+            Message {
+                log_group: log_group.clone(),
+                log_stream: log_stream.clone(),
+                url,
+                ..message(content)
+            },
+        );
+    }
+
+    let datetime = TIMESTAMP_FIELDS
+        .iter()
+        .find_map(|field| value.get(field).and_then(json_timestamp));
+    let content = line.map_or_else(|| value.to_string(), str::to_string);
+    (datetime, message(content))
+}
+
+/// An RFC 3339 string, or epoch milliseconds or seconds.
+fn json_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::String(text) => DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|datetime| datetime.to_utc()),
+        Value::Number(number) => {
+            let number = number.as_i64()?;
+            if number > 100_000_000_000 {
+                DateTime::from_timestamp_millis(number)
+            } else {
+                DateTime::from_timestamp(number, 0)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Timestamp at the start of a line, also after a Lambda `[ERROR]` prefix.
+fn leading_timestamp(line: &str) -> Option<DateTime<Utc>> {
+    line.split_whitespace().take(2).find_map(|token| {
+        DateTime::parse_from_rfc3339(token)
+            .ok()
+            .map(|datetime| datetime.to_utc())
+    })
+}
+
+/// Group the logs with the same time and compute the wait before each group at the speed.
+fn schedule(messages: Vec<Message>, speed: f64) -> Vec<(Duration, Vec<Message>)> {
+    let mut batches: Vec<(Duration, Vec<Message>)> = Vec::new();
+    let mut previous: Option<DateTime<Utc>> = None;
+    // これまでで最も新しい時刻。時刻が戻った後もここから待つ
+    let mut latest: Option<DateTime<Utc>> = None;
+    for message in messages {
+        if previous == Some(message.datetime) {
+            batches.last_mut().unwrap().1.push(message);
+            continue;
+        }
+        // 時刻が戻っている場合は待たない
+        let wait = latest
+            .and_then(|latest| (message.datetime - latest).to_std().ok())
+            .map_or(Duration::ZERO, |gap| gap.div_f64(speed));
+        previous = Some(message.datetime);
+        latest = latest.max(previous);
+        batches.push((wait, vec![message]));
+    }
+    batches
+}
+
+/// Send the logs to the Log Stream at once, or at `speed` times the pace of their timestamps.
+pub async fn run(messages: Vec<Message>, speed: Option<f64>, tx: UnboundedSender<Action>) {
+    let count = messages.len();
+    let batches = match speed {
+        Some(speed) => schedule(messages, speed),
+        None => vec![(Duration::ZERO, messages)],
+    };
+    for (wait, new_messages) in batches {
+        tokio::time::sleep(wait).await;
+        if tx
+            .send(Action::ComponentAction(Box::new(ReceiveNewLog {
+                new_messages,
+            })))
+            .is_err()
+        {
+            return;
+        }
+    }
+    let _ = tx.send(Action::ComponentAction(Box::new(ShowToast {
+        title: "Replay".to_string(),
+        body: format!("Loaded {count} logs"),
+    })));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write(dir: &tempfile::TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_exported_ndjson() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(
+            &dir,
+            "replay.jsonl",
+            concat!(
+                r#"{"timestamp":"2025-06-07T19:00:00.000+09:00","log_group":"/aws/lambda/app","log_stream":"s1","url":"https://example.com","message":{"level":"ERROR"}}"#,
+                "\n",
+                r#"{"timestamp":"2025-06-07T10:00:01.500Z","log_group":"/aws/lambda/app","log_stream":"s1","url":"","message":"plain"}"#,
+                "\n\n",
+            ),
+        );
+        let messages = read_logs(&path).unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].content, r#"{"level":"ERROR"}"#);
+        assert_eq!(messages[0].log_group, "/aws/lambda/app");
+        assert_eq!(messages[0].url, "https://example.com");
+        assert_eq!(
+            messages[0].datetime,
+            "2025-06-07T10:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(messages[1].content, "plain");
+        assert_eq!(
+            messages[1].datetime - messages[0].datetime,
+            chrono::Duration::milliseconds(1500)
+        );
+    }
+
+    #[test]
+    fn test_read_plain_logs() {
+        // 旧形式のエクスポートは本文だけの行
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(
+            &dir,
+            "replay.log",
+            concat!(
+                "START RequestId: abc\n",
+                "2025-06-07T10:00:00.000Z\tabc\tINFO\thello\n",
+                "[ERROR]\t2025-06-07T10:00:02.000Z\tabc\tfailed\n",
+                "  at handler\n",
+                r#"{"level": "info", "time": 1749290405000}"#,
+                "\n",
+            ),
+        );
+        let messages = read_logs(&path).unwrap();
+
+        let seconds: Vec<i64> = messages
+            .iter()
+            .map(|message| message.datetime.timestamp() - 1749290400)
+            .collect();
+        assert_eq!(seconds, vec![0, 0, 2, 2, 5]);
+        assert_eq!(messages[3].content, "  at handler");
+        assert_eq!(
+            messages[4].content,
+            r#"{"level": "info", "time": 1749290405000}"#
+        );
+        assert_eq!(messages[4].log_group, "");
+    }
+
+    #[test]
+    fn test_read_json_array() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(
+            &dir,
+            "replay.json",
+            r#"[
+  {"timestamp": "2025-06-07T10:00:00.000Z", "log_group": "g", "log_stream": "s", "url": "", "message": "a"},
+  {"timestamp": "2025-06-07T10:00:01.000Z", "log_group": "g", "log_stream": "s", "url": "", "message": "b"}
+]"#,
+        );
+        let messages = read_logs(&path).unwrap();
+
+        let contents: Vec<&str> = messages
+            .iter()
+            .map(|message| message.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["a", "b"]);
+        assert!(read_logs(Path::new("/nonexistent/cwlogs-viewer.jsonl")).is_err());
+    }
+
+    #[test]
+    fn test_schedule() {
+        let start = "2025-06-07T10:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let messages: Vec<Message> = [0, 0, 4, 2, 10]
+            .into_iter()
+            .map(|seconds| Message {
+                datetime: start + chrono::Duration::seconds(seconds),
+                ..message(seconds.to_string())
+            })
+            .collect();
+
+        let batches: Vec<(Duration, usize)> = schedule(messages, 2.0)
+            .into_iter()
+            .map(|(wait, batch)| (wait, batch.len()))
+            .collect();
+        assert_eq!(
+            batches,
+            vec![
+                (Duration::ZERO, 2),
+                (Duration::from_secs(2), 1),
+                (Duration::ZERO, 1),
+                (Duration::from_secs(3), 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Utc};
+use color_eyre::{Result, eyre::eyre};
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::components::outer_layout::Message;
+
+/// Number of messages written to one segment file.
+const SEGMENT_LEN: usize = 1000;
+
+/// `Message` serializes only its content for exports, so segments use their own record.
+#[derive(Debug, Serialize, Deserialize)]
+struct Record {
+    id: String,
+    content: String,
+    datetime: DateTime<Utc>,
+    log_group: String,
+    log_stream: String,
+    url: String,
+}
+
+impl From<&Message> for Record {
+    fn from(message: &Message) -> Self {
+        Self {
+            id: message.id.to_string(),
+            content: message.content.clone(),
+            datetime: message.datetime,
+            log_group: message.log_group.clone(),
+            log_stream: message.log_stream.clone(),
+            url: message.url.clone(),
+        }
+    }
+}
+
+impl TryFrom<Record> for Message {
+    type Error = color_eyre::Report;
+
+    fn try_from(record: Record) -> Result<Self> {
+        Ok(Self {
+            id: Ulid::from_string(&record.id)?,
+            content: record.content,
+            datetime: record.datetime,
+            log_group: record.log_group,
+            log_stream: record.log_stream,
+            url: record.url,
+        })
+    }
+}
+
+/// Directory of one session. Removed when the last store using it is dropped.
+#[derive(Debug)]
+struct SessionDir(PathBuf);
+
+impl Drop for SessionDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Append-only store of the logs evicted from the Log Stream.
+///
+/// Messages are numbered from 0 in the order they are appended and split into segment files of
+/// `SEGMENT_LEN` lines, so a range can be read back without scanning older segments.
+#[derive(Clone, Debug, Default)]
+pub struct Scrollback {
+    /// `None` disables the store and evicted logs are dropped.
+    dir: Option<Arc<SessionDir>>,
+    len: usize,
+}
+
+impl Scrollback {
+    /// Store the segments in a new directory under `root`. The directory is created on the first
+    /// write.
+    pub fn new(root: &Path) -> Self {
+        Self {
+            dir: Some(Arc::new(SessionDir(root.join(Ulid::new().to_string())))),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    fn segment_path(dir: &Path, segment: usize) -> PathBuf {
+        dir.join(format!("segment-{segment:06}.jsonl"))
+    }
+
+    pub fn append(&mut self, messages: &[Message]) -> Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        fs::create_dir_all(&dir.0)?;
+
+        let mut rest = messages;
+        while !rest.is_empty() {
+            // セグメントの境界で分割して書き込む
+            let segment = self.len / SEGMENT_LEN;
+            let count = (SEGMENT_LEN - self.len % SEGMENT_LEN).min(rest.len());
+            let mut writer = BufWriter::new(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(Self::segment_path(&dir.0, segment))?,
+            );
+            for message in &rest[..count] {
+                serde_json::to_writer(&mut writer, &Record::from(message))?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()?;
+            self.len += count;
+            rest = &rest[count..];
+        }
+        Ok(())
+    }
+
+    /// Read the messages numbered in `range`.
+    pub fn read(&self, range: Range<usize>) -> Result<Vec<Message>> {
+        if range.is_empty() {
+            return Ok(Vec::new());
+        }
+        let Some(dir) = &self.dir else {
+            return Err(eyre!("Scrollback is disabled"));
+        };
+        if range.end > self.len {
+            return Err(eyre!(
+                "Scrollback has {} logs, requested up to {}",
+                self.len,
+                range.end
+            ));
+        }
+
+        let mut messages = Vec::with_capacity(range.len());
+        for segment in range.start / SEGMENT_LEN..=(range.end - 1) / SEGMENT_LEN {
+            let segment_start = segment * SEGMENT_LEN;
+            let file = File::open(Self::segment_path(&dir.0, segment))?;
+            for (index, line) in BufReader::new(file).lines().enumerate() {
+                let number = segment_start + index;
+                if number >= range.end {
+                    break;
+                }
+                if number >= range.start {
+                    let record: Record = serde_json::from_str(&line?)?;
+                    messages.push(record.try_into()?);
+                }
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Remove every segment, e.g. when a new search replaces the logs.
+    pub fn clear(&mut self) -> Result<()> {
+        if let Some(dir) = &self.dir
+            && dir.0.exists()
+        {
+            fs::remove_dir_all(&dir.0)?;
+        }
+        self.len = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::components::outer_layout::message;
+
+    fn messages(range: Range<usize>) -> Vec<Message> {
+        range
+            .map(|index| message(&format!("log {index}")))
+            .collect()
+    }
+
+    #[test]
+    fn test_append_and_read_across_segments() {
+        let root = tempfile::tempdir().unwrap();
+        let mut scrollback = Scrollback::new(root.path());
+        let all = messages(0..2500);
+        scrollback.append(&all[..700]).unwrap();
+        scrollback.append(&all[700..]).unwrap();
+        assert_eq!(scrollback.len(), 2500);
+
+        let read = scrollback.read(950..2050).unwrap();
+        assert_eq!(read.len(), 1100);
+        assert_eq!(read[0], all[950]);
+        assert_eq!(read[1099], all[2049]);
+        assert!(scrollback.read(2400..2501).is_err());
+
+        scrollback.clear().unwrap();
+        assert_eq!(scrollback.len(), 0);
+
+        let dir = scrollback.dir.as_ref().unwrap().0.clone();
+        scrollback.append(&all[..1]).unwrap();
+        assert!(dir.exists());
+        drop(scrollback);
+        assert!(!dir.exists());
+    }
+}
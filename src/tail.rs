@@ -0,0 +1,329 @@
+use std::{
+    fs::{self, File, Metadata},
+    io::{self, BufRead, BufReader, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::Utc;
+use color_eyre::{Result, eyre::eyre};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::{
+    action::Action,
+    components::{
+        outer_layout::{Message, ReceiveNewLog},
+        toast::ShowToast,
+    },
+    replay::parse_line,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Longest wait between polls while the file is missing.
+const MAX_BACKOFF: Duration = Duration::from_secs(4);
+/// Upper bound of lines sent in one `ReceiveNewLog`.
+const MAX_BATCH: usize = 1000;
+
+/// A source followed like `tail -F`.
+pub enum TailSource {
+    Stdin,
+    File(FileFollower),
+}
+
+impl TailSource {
+    /// `-` is stdin. Files must exist so that typos fail before the TUI starts.
+    pub fn open(path: &Path) -> Result<Self> {
+        if path == Path::new("-") {
+            Ok(TailSource::Stdin)
+        } else {
+            Ok(TailSource::File(FileFollower::new(path)?))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// Renames can't be told apart without an inode, so only truncation is detected.
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Reads the lines appended to a file, reopening it when it is rotated or truncated.
+pub struct FileFollower {
+    path: PathBuf,
+    reader: BufReader<File>,
+    id: Option<(u64, u64)>,
+    position: u64,
+    /// Bytes of a line whose newline hasn't been written yet
+    partial: Vec<u8>,
+    /// The path was missing at the last poll, e.g. between the rename and the create of a rotation
+    missing: bool,
+}
+
+impl FileFollower {
+    /// Start at the end of the file like `tail -f`.
+    pub fn new(path: &Path) -> Result<Self> {
+        let mut follower = Self::open(path)?;
+        follower.position = follower.reader.seek(SeekFrom::End(0))?;
+        Ok(follower)
+    }
+
+    fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).map_err(|e| eyre!("Failed to open {}: {e}", path.display()))?;
+        let id = file_id(&file.metadata()?);
+        Ok(Self {
+            path: path.to_path_buf(),
+            reader: BufReader::new(file),
+            id,
+            position: 0,
+            partial: Vec::new(),
+            missing: false,
+        })
+    }
+
+    /// Lines completed since the last poll.
+    pub fn poll(&mut self) -> Result<Vec<String>> {
+        let metadata = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                // ローテーション中でファイルがまだ無い
+                self.missing = true;
+                return self.read_lines();
+            }
+            Err(e) => return Err(e.into()),
+        };
+        self.missing = false;
+        if file_id(&metadata) != self.id {
+            // 古いファイルの残りを読んでから新しいファイルを先頭から読む
+            let mut lines = self.read_lines()?;
+            let file = match File::open(&self.path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    // 確認した直後に消えた場合は次のポーリングで開き直す
+                    self.missing = true;
+                    return Ok(lines);
+                }
+                Err(e) => return Err(eyre!("Failed to open {}: {e}", self.path.display())),
+            };
+            let rest = std::mem::take(&mut self.partial);
+            if !rest.is_empty() {
+                lines.push(String::from_utf8_lossy(&rest).into_owned());
+            }
+            self.id = file_id(&file.metadata()?);
+            self.reader = BufReader::new(file);
+            self.position = 0;
+            lines.extend(self.read_lines()?);
+            return Ok(lines);
+        }
+        if metadata.len() < self.position {
+            // 切り詰められた
+            self.position = self.reader.seek(SeekFrom::Start(0))?;
+            self.partial.clear();
+        }
+        self.read_lines()
+    }
+
+    fn read_lines(&mut self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        loop {
+            let read = self.reader.read_until(b'\n', &mut self.partial)?;
+            if read == 0 {
+                break;
+            }
+            self.position += read as u64;
+            if self.partial.ends_with(b"\n") {
+                let line = String::from_utf8_lossy(&self.partial);
+                lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+                self.partial.clear();
+            }
+        }
+        Ok(lines)
+    }
+}
+
+/// Turn lines into logs. Lines without a timestamp get the time they were read.
+fn to_messages(lines: Vec<String>, label: &str) -> Vec<Message> {
+    lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (datetime, mut message) = parse_line(line);
+            message.datetime = datetime.unwrap_or_else(Utc::now);
+            if message.log_stream.is_empty() {
+                message.log_stream = label.to_string();
+            }
+            message
+        })
+        .collect()
+}
+
+fn send(tx: &UnboundedSender<Action>, new_messages: Vec<Message>) -> bool {
+    new_messages.is_empty()
+        || tx
+            .send(Action::ComponentAction(Box::new(ReceiveNewLog {
+                new_messages,
+            })))
+            .is_ok()
+}
+
+async fn follow_file(mut follower: FileFollower, tx: UnboundedSender<Action>) {
+    let label = follower.path.display().to_string();
+    let mut delay = POLL_INTERVAL;
+    while !tx.is_closed() {
+        tokio::time::sleep(delay).await;
+        match follower.poll() {
+            Ok(lines) => {
+                for lines in lines.chunks(MAX_BATCH) {
+                    if !send(&tx, to_messages(lines.to_vec(), &label)) {
+                        return;
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = tx.send(Action::Error(format!("Failed to read {label}: {e:#}")));
+                return;
+            }
+        }
+        // ファイルが無い間は間隔を空けて待つ
+        delay = if follower.missing {
+            (delay * 2).min(MAX_BACKOFF)
+        } else {
+            POLL_INTERVAL
+        };
+    }
+}
+
+async fn follow_stdin(tx: UnboundedSender<Action>) {
+    let (line_tx, mut line_rx) = mpsc::unbounded_channel();
+    // tokioのstdinは読み込みを中断できず終了時に止まるので、専用のスレッドで読む
+    std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            match stdin.read_until(b'\n', &mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buffer);
+                    let line = line.trim_end_matches(['\n', '\r']).to_string();
+                    if line_tx.send(line).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    while let Some(line) = line_rx.recv().await {
+        let mut lines = vec![line];
+        while lines.len() < MAX_BATCH
+            && let Ok(line) = line_rx.try_recv()
+        {
+            lines.push(line);
+        }
+        if !send(&tx, to_messages(lines, "stdin")) {
+            return;
+        }
+    }
+    let _ = tx.send(Action::ComponentAction(Box::new(ShowToast {
+        title: "stdin".to_string(),
+        body: "Reached the end of stdin".to_string(),
+    })));
+}
+
+/// Send the lines of every source to the Log Stream as they arrive.
+pub async fn run(sources: Vec<TailSource>, tx: UnboundedSender<Action>) {
+    let tasks = sources.into_iter().map(|source| match source {
+        TailSource::Stdin => tokio::spawn(follow_stdin(tx.clone())),
+        TailSource::File(follower) => tokio::spawn(follow_file(follower, tx.clone())),
+    });
+    futures::future::join_all(tasks).await;
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::*;
+
+    fn append(path: &Path, content: &str) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_follow_appended_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        fs::write(&path, "old line\n").unwrap();
+
+        let mut follower = FileFollower::new(&path).unwrap();
+        assert!(follower.poll().unwrap().is_empty());
+
+        append(&path, "first\r\nsecond\npart");
+        assert_eq!(follower.poll().unwrap(), vec!["first", "second"]);
+        append(&path, "ial\n");
+        assert_eq!(follower.poll().unwrap(), vec!["partial"]);
+
+        // 切り詰められたら先頭から読み直す
+        fs::write(&path, "").unwrap();
+        assert!(follower.poll().unwrap().is_empty());
+        append(&path, "after truncate\n");
+        assert_eq!(follower.poll().unwrap(), vec!["after truncate"]);
+
+        assert!(FileFollower::new(Path::new("/nonexistent/cwlogs-viewer.log")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_rotated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        let rotated = dir.path().join("app.log.1");
+        fs::write(&path, "").unwrap();
+
+        let mut follower = FileFollower::new(&path).unwrap();
+        append(&path, "before\n");
+        fs::rename(&path, &rotated).unwrap();
+        append(&rotated, "late write\n");
+        // 新しいファイルができるまでは古いファイルを読み続ける
+        assert_eq!(follower.poll().unwrap(), vec!["before", "late write"]);
+        assert!(follower.missing);
+
+        append(&rotated, "last");
+        append(&path, "new file\n");
+        assert_eq!(follower.poll().unwrap(), vec!["last", "new file"]);
+        assert!(!follower.missing);
+        append(&path, "more\n");
+        assert_eq!(follower.poll().unwrap(), vec!["more"]);
+    }
+
+    #[test]
+    fn test_to_messages() {
+        let messages = to_messages(
+            vec![
+                r#"{"timestamp": "2025-06-07T10:00:00Z", "level": "INFO"}"#.to_string(),
+                String::new(),
+                "plain".to_string(),
+            ],
+            "app.log",
+        );
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0].content,
+            r#"{"timestamp": "2025-06-07T10:00:00Z", "level": "INFO"}"#
+        );
+        assert_eq!(messages[0].datetime.timestamp(), 1749290400);
+        assert_eq!(messages[1].log_stream, "app.log");
+        assert!(messages[1].datetime > messages[0].datetime);
+    }
+}